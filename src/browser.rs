@@ -0,0 +1,183 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::path::{Path, PathBuf};
+
+use crate::ui::TextInput;
+
+/// Name of the entry that chooses the browsed directory itself
+pub const CURRENT_DIRECTORY: &str = ".";
+
+/// In-plugin directory browser used to pick the folder of a new session
+#[derive(Default)]
+pub struct DirectoryBrowser {
+    /// Directory being browsed (host path)
+    directory: PathBuf,
+    /// Names of its subdirectories, sorted
+    subdirectories: Vec<String>,
+    /// Fuzzy filter typed by the user
+    filter: TextInput,
+    /// Show subdirectories starting with '.'
+    show_hidden: bool,
+    /// Index of the selected entry in `entries()`
+    selected: usize,
+    matcher: SkimMatcherV2,
+}
+
+impl DirectoryBrowser {
+    /// Browse a directory, given the names of its subdirectories
+    /// Clears the filter and selects the first entry
+    pub fn open(&mut self, directory: PathBuf, mut subdirectories: Vec<String>) {
+        subdirectories.sort_by_key(|name| name.to_lowercase());
+        self.directory = directory;
+        self.subdirectories = subdirectories;
+        self.filter.clear();
+        self.selected = 0;
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn filter(&self) -> &TextInput {
+        &self.filter
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    /// Show or hide subdirectories starting with '.'
+    pub fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        self.selected = 0;
+    }
+
+    /// Edit the filter with a key, returning false if the key doesn't edit text
+    pub fn handle_filter_key(&mut self, key: &zellij_tile::prelude::KeyWithModifier) -> bool {
+        let before = self.filter.text().to_string();
+        if !self.filter.handle_key(key) {
+            return false;
+        }
+        if self.filter.text() != before {
+            self.selected = 0;
+        }
+        true
+    }
+
+    /// Entries matching the filter with their matched character indices
+    /// The current directory entry is listed first while nothing is typed
+    pub fn entries(&self) -> Vec<(&str, Vec<usize>)> {
+        let visible = self
+            .subdirectories
+            .iter()
+            .filter(|name| self.show_hidden || !name.starts_with('.'));
+        if self.filter.is_empty() {
+            return std::iter::once(CURRENT_DIRECTORY)
+                .chain(visible.map(String::as_str))
+                .map(|name| (name, Vec::new()))
+                .collect();
+        }
+
+        let mut matches: Vec<(i64, &str, Vec<usize>)> = visible
+            .filter_map(|name| {
+                self.matcher
+                    .fuzzy_indices(name, self.filter.text())
+                    .map(|(score, indices)| (score, name.as_str(), indices))
+            })
+            .collect();
+        // Stable sort keeps alphabetical order for equal scores
+        matches.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        matches
+            .into_iter()
+            .map(|(_, name, indices)| (name, indices))
+            .collect()
+    }
+
+    /// Index of the selected entry, if there are any entries
+    pub fn selected_index(&self) -> Option<usize> {
+        let len = self.entries().len();
+        (len > 0).then(|| self.selected.min(len - 1))
+    }
+
+    pub fn move_up(&mut self) {
+        if let Some(index) = self.selected_index() {
+            self.selected = index.saturating_sub(1);
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if let Some(index) = self.selected_index() {
+            self.selected = (index + 1).min(self.entries().len() - 1);
+        }
+    }
+
+    /// Path of the selected entry (the browsed directory for the current directory entry)
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let index = self.selected_index()?;
+        let (name, _) = self.entries().get(index)?.clone();
+        Some(if name == CURRENT_DIRECTORY {
+            self.directory.clone()
+        } else {
+            self.directory.join(name)
+        })
+    }
+
+    /// Subdirectory to descend into: the selected one (not the current directory entry)
+    pub fn child(&self) -> Option<PathBuf> {
+        self.selected_path()
+            .filter(|path| path.as_path() != self.directory.as_path())
+    }
+
+    /// Parent of the browsed directory
+    pub fn parent(&self) -> Option<PathBuf> {
+        self.directory.parent().map(Path::to_path_buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser(names: &[&str]) -> DirectoryBrowser {
+        let mut browser = DirectoryBrowser::default();
+        browser.open(
+            PathBuf::from("/home/u"),
+            names.iter().map(|name| name.to_string()).collect(),
+        );
+        browser
+    }
+
+    fn names(browser: &DirectoryBrowser) -> Vec<&str> {
+        browser
+            .entries()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_hide_dot_directories() {
+        let mut browser = browser(&["src", ".config", "Notes"]);
+        assert_eq!(names(&browser), vec![".", "Notes", "src"]);
+        assert_eq!(browser.selected_path(), Some(PathBuf::from("/home/u")));
+        assert_eq!(browser.child(), None);
+
+        browser.toggle_hidden();
+        assert_eq!(names(&browser), vec![".", ".config", "Notes", "src"]);
+        browser.move_down();
+        assert_eq!(browser.child(), Some(PathBuf::from("/home/u/.config")));
+        assert_eq!(browser.parent(), Some(PathBuf::from("/home")));
+    }
+
+    #[test]
+    fn test_filter_ranks_matches() {
+        use zellij_tile::prelude::{BareKey, KeyWithModifier};
+
+        let mut browser = browser(&["docs", "dotfiles", "src"]);
+        for c in "dcs".chars() {
+            browser.handle_filter_key(&KeyWithModifier::new(BareKey::Char(c)));
+        }
+        assert_eq!(names(&browser), vec!["docs"]);
+        assert_eq!(browser.selected_path(), Some(PathBuf::from("/home/u/docs")));
+    }
+}
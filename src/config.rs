@@ -1,4 +1,8 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::keybindings::Keymap;
+use crate::zoxide::{DirectoryFilter, DirectorySource, ZoxideCommand};
 
 /// Session list sort order
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -20,6 +24,138 @@ impl SortOrder {
     }
 }
 
+/// How items are nested into folders within the main list sections
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum GroupBy {
+    /// Flat list (default)
+    #[default]
+    None,
+    /// Nest items under the configured base path containing them
+    BasePath,
+    /// Nest items under the git repository containing them
+    GitRoot,
+}
+
+impl GroupBy {
+    /// Parse grouping from config string (case-insensitive)
+    fn from_config_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "base_path" => GroupBy::BasePath,
+            "git_root" => GroupBy::GitRoot,
+            _ => GroupBy::None, // "none" or any other value keeps the flat list
+        }
+    }
+}
+
+/// How the folder of a new session is picked
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FolderPicker {
+    /// The `filepicker` plugin, or the built-in browser if it never answered (default)
+    #[default]
+    Filepicker,
+    /// Always the built-in directory browser
+    Builtin,
+}
+
+impl FolderPicker {
+    /// Parse folder picker from config string (case-insensitive)
+    fn from_config_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "builtin" => FolderPicker::Builtin,
+            _ => FolderPicker::Filepicker,
+        }
+    }
+}
+
+/// Automatic cleanup policy for resurrectable sessions
+#[derive(Debug, Clone, Default)]
+pub struct CleanupPolicy {
+    /// Delete resurrectable sessions older than this
+    pub max_age: Option<Duration>,
+    /// Keep at most this many resurrectable sessions (most recent first)
+    pub max_count: Option<usize>,
+    /// Session names or glob patterns that are never cleaned up
+    pub exclude: Vec<String>,
+    /// Only preview sessions that would be deleted instead of deleting them
+    pub dry_run: bool,
+}
+
+impl CleanupPolicy {
+    /// Whether any cleanup limit is configured
+    pub fn is_enabled(&self) -> bool {
+        self.max_age.is_some() || self.max_count.is_some()
+    }
+}
+
+/// Filesystem search for directories not in zoxide (disabled without roots)
+#[derive(Debug, Clone)]
+pub struct DiskSearch {
+    /// Directories searched below, `~/` is expanded
+    pub roots: Vec<String>,
+    /// How many levels below each root are searched
+    pub max_depth: usize,
+    /// Stop after this many directories per root
+    pub max_results: usize,
+}
+
+impl Default for DiskSearch {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            max_depth: 4,
+            max_results: 50,
+        }
+    }
+}
+
+/// Shortest `refresh_interval`: each refresh re-runs every directory source and git root lookup
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Split a pipe-separated config value into trimmed, non-empty entries
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split('|')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Split a pipe-separated list of path patterns into trimmed, non-empty entries
+/// `\\|` stands for a literal `|`, so regexes can use alternation
+fn split_pattern_list(value: &str) -> Vec<String> {
+    let mut entries = vec![String::new()];
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                entries.last_mut().unwrap().push('|');
+            }
+            '|' => entries.push(String::new()),
+            _ => entries.last_mut().unwrap().push(c),
+        }
+    }
+    entries
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Parse a numeric config value, reporting values that are not numbers
+fn parse_number<T: std::str::FromStr>(
+    config: &BTreeMap<String, String>,
+    key: &str,
+    errors: &mut Vec<String>,
+) -> Option<T> {
+    let value = config.get(key)?;
+    let parsed = value.trim().parse::<T>().ok();
+    if parsed.is_none() {
+        errors.push(format!("{} must be a number, ignoring '{}'", key, value));
+    }
+    parsed
+}
+
 /// Plugin configuration loaded from Zellij layout
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -33,6 +169,32 @@ pub struct Config {
     pub show_all_sessions: bool,
     /// Sort order for session list (default: MRU)
     pub sort_order: SortOrder,
+    /// Folder nesting of the main list (default: none)
+    pub group_by: GroupBy,
+    /// How the folder of a new session is picked
+    pub folder_picker: FolderPicker,
+    /// How zoxide is invoked
+    pub zoxide_command: ZoxideCommand,
+    /// Run `zoxide add` for the directory of a session created through zsm
+    pub zoxide_add_on_create: bool,
+    /// Run `zoxide add` for the recorded directory of a session switched to through zsm
+    pub zoxide_add_on_switch: bool,
+    /// Where directories are read from, in priority order (default: zoxide)
+    pub directory_sources: Vec<DirectorySource>,
+    /// Which zoxide directories are listed (all by default)
+    pub directory_filter: DirectoryFilter,
+    /// Filesystem search run when the search term has no good matches
+    pub disk_search: DiskSearch,
+    /// Re-read directories and update session ages this often (disabled by default, at least 5s)
+    pub refresh_interval: Option<Duration>,
+    /// Cleanup policy for resurrectable sessions (disabled by default)
+    pub resurrectable_cleanup: CleanupPolicy,
+    /// Session names or glob patterns that cannot be killed without typing their name
+    pub protected_sessions: Vec<String>,
+    /// Keys bound to each action
+    pub keymap: Keymap,
+    /// Config entries that could not be parsed (besides keybindings and path filters)
+    pub errors: Vec<String>,
 }
 
 impl Default for Config {
@@ -43,6 +205,19 @@ impl Default for Config {
             base_paths: Vec::new(),
             show_all_sessions: false,
             sort_order: SortOrder::default(),
+            group_by: GroupBy::default(),
+            folder_picker: FolderPicker::default(),
+            zoxide_command: ZoxideCommand::default(),
+            zoxide_add_on_create: false,
+            zoxide_add_on_switch: false,
+            directory_sources: vec![DirectorySource::Zoxide],
+            directory_filter: DirectoryFilter::default(),
+            disk_search: DiskSearch::default(),
+            refresh_interval: None,
+            resurrectable_cleanup: CleanupPolicy::default(),
+            protected_sessions: Vec::new(),
+            keymap: Keymap::default(),
+            errors: Vec::new(),
         }
     }
 }
@@ -50,6 +225,43 @@ impl Default for Config {
 impl Config {
     /// Create configuration from Zellij plugin configuration
     pub fn from_zellij_config(config: &BTreeMap<String, String>) -> Self {
+        let mut errors = Vec::new();
+        let directory_sources = match config.get("directory_sources") {
+            Some(sources) => split_list(sources)
+                .iter()
+                .filter_map(|spec| {
+                    DirectorySource::parse(spec)
+                        .map_err(|err| errors.push(err))
+                        .ok()
+                })
+                .collect(),
+            None => vec![DirectorySource::Zoxide],
+        };
+        let (zoxide_command, zoxide_errors) = ZoxideCommand::from_config(
+            config.get("zoxide_path").map(String::as_str),
+            config.get("zoxide_data_dir").map(String::as_str),
+            config.get("zoxide_args").map(String::as_str),
+            &config
+                .get("zoxide_env")
+                .map(|v| split_list(v))
+                .unwrap_or_default(),
+        );
+        errors.extend(zoxide_errors);
+        let refresh_interval = config
+            .get("refresh_interval")
+            .and_then(|v| humantime::parse_duration(v.trim()).ok())
+            .filter(|interval| !interval.is_zero())
+            .map(|interval| {
+                if interval < MIN_REFRESH_INTERVAL {
+                    errors.push(format!(
+                        "refresh_interval must be at least {}, using that instead of {}",
+                        humantime::format_duration(MIN_REFRESH_INTERVAL),
+                        humantime::format_duration(interval)
+                    ));
+                }
+                interval.max(MIN_REFRESH_INTERVAL)
+            });
+
         Self {
             default_layout: config.get("default_layout").cloned(),
             session_separator: config
@@ -58,13 +270,7 @@ impl Config {
                 .unwrap_or_else(|| ".".to_string()),
             base_paths: config
                 .get("base_paths")
-                .map(|paths| {
-                    paths
-                        .split('|')
-                        .map(|p| p.trim().to_string())
-                        .filter(|p| !p.is_empty())
-                        .collect()
-                })
+                .map(|paths| split_list(paths))
                 .unwrap_or_default(),
             show_all_sessions: config
                 .get("show_all_sessions")
@@ -74,6 +280,115 @@ impl Config {
                 .get("sort_order")
                 .map(|v| SortOrder::from_config_str(v))
                 .unwrap_or_default(),
+            group_by: config
+                .get("group_by")
+                .map(|v| GroupBy::from_config_str(v))
+                .unwrap_or_default(),
+            folder_picker: config
+                .get("folder_picker")
+                .map(|v| FolderPicker::from_config_str(v))
+                .unwrap_or_default(),
+            zoxide_command,
+            zoxide_add_on_create: config
+                .get("zoxide_add_on_create")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            zoxide_add_on_switch: config
+                .get("zoxide_add_on_switch")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            directory_sources,
+            directory_filter: DirectoryFilter::new(
+                &config
+                    .get("exclude_paths")
+                    .map(|v| split_pattern_list(v))
+                    .unwrap_or_default(),
+                &config
+                    .get("include_paths")
+                    .map(|v| split_pattern_list(v))
+                    .unwrap_or_default(),
+                parse_number(config, "min_score", &mut errors),
+                parse_number(config, "max_entries", &mut errors),
+            ),
+            disk_search: DiskSearch {
+                roots: config
+                    .get("disk_search_roots")
+                    .map(|v| split_list(v))
+                    .unwrap_or_default(),
+                max_depth: config
+                    .get("disk_search_depth")
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .unwrap_or(DiskSearch::default().max_depth),
+                max_results: config
+                    .get("disk_search_limit")
+                    .and_then(|v| v.trim().parse::<usize>().ok())
+                    .unwrap_or(DiskSearch::default().max_results),
+            },
+            refresh_interval,
+            resurrectable_cleanup: CleanupPolicy {
+                max_age: config
+                    .get("resurrectable_max_age")
+                    .and_then(|v| humantime::parse_duration(v.trim()).ok()),
+                max_count: config
+                    .get("resurrectable_max_count")
+                    .and_then(|v| v.trim().parse::<usize>().ok()),
+                exclude: config
+                    .get("resurrectable_cleanup_exclude")
+                    .map(|v| split_list(v))
+                    .unwrap_or_default(),
+                dry_run: config
+                    .get("resurrectable_cleanup_dry_run")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+            },
+            protected_sessions: config
+                .get("protected_sessions")
+                .map(|v| split_list(v))
+                .unwrap_or_default(),
+            keymap: Keymap::from_config(
+                config.get("keybinding_preset").map(String::as_str),
+                config.get("keybindings").map(String::as_str),
+                config.get("normal_keybindings").map(String::as_str),
+            ),
+            errors,
         }
     }
+
+    /// All problems found while parsing the configuration
+    pub fn errors(&self) -> Vec<&str> {
+        self.keymap
+            .errors()
+            .iter()
+            .chain(&self.directory_filter.errors)
+            .chain(&self.errors)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_list_escaped_pipe() {
+        assert_eq!(
+            split_pattern_list("/tmp/* | re:/(target\\|node_modules)$|"),
+            vec!["/tmp/*", "re:/(target|node_modules)$"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_numbers_are_reported() {
+        let config = BTreeMap::from([
+            ("min_score".to_string(), "high".to_string()),
+            ("max_entries".to_string(), "100".to_string()),
+        ]);
+        let config = Config::from_zellij_config(&config);
+        assert_eq!(config.directory_filter.max_entries, Some(100));
+        assert_eq!(
+            config.errors,
+            vec!["min_score must be a number, ignoring 'high'"]
+        );
+    }
 }
@@ -0,0 +1,589 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use zellij_tile::prelude::*;
+
+/// A named plugin action that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    /// Move the selection up
+    Up,
+    /// Move the selection down
+    Down,
+    /// Move the selection up by one page
+    PageUp,
+    /// Move the selection down by one page
+    PageDown,
+    /// Select the first item
+    First,
+    /// Select the last item
+    Last,
+    /// Switch to the selected session or create one for the selected directory
+    Switch,
+    /// Create a session with the default layout
+    QuickCreate,
+    /// Kill or delete the selected (or marked) sessions
+    Kill,
+    /// Mark the selected session
+    Mark,
+    /// Mark or unmark every visible session
+    MarkAll,
+    /// Undo the most recent kill or deletion
+    Undo,
+    /// Clear the search, then the marks, then hide the plugin
+    Back,
+    /// Hide the plugin
+    Exit,
+    /// Reload zoxide directories
+    Reload,
+    /// Rename the selected session
+    Rename,
+    /// Toggle visibility of resurrectable sessions
+    ToggleDead,
+    /// Toggle deletion protection of the selected session
+    Protect,
+    /// Review the resurrectable session cleanup preview
+    Cleanup,
+    /// Collapse or expand the section of the selected item
+    ToggleGroup,
+    /// Remove the selected vanished directory from zoxide
+    Prune,
+    /// Remove every vanished directory from zoxide
+    PruneAll,
+    /// Show what was read from each directory source
+    Diagnostics,
+    /// Open the selected directory as a new tab in the current session
+    OpenTab,
+    /// Pick a layout, then open the selected directory as a new tab with it
+    OpenTabLayout,
+    /// Open the selected directory as a new tiled pane in the current tab
+    OpenPane,
+    /// Open the selected directory as a new floating pane in the current tab
+    OpenFloating,
+    /// Pick a folder for a new session
+    PickFolder,
+    /// Pick a folder for a new session with the built-in directory browser
+    BrowseFolder,
+    /// Clear the folder of a new session
+    ClearFolder,
+    /// Type the folder of a new session
+    EditFolder,
+    /// Show or hide hidden directories in the directory browser
+    ToggleHidden,
+    /// Go back from layout selection to session name entry
+    EditName,
+    /// Confirm a dialog
+    Confirm,
+    /// Cancel a dialog
+    Cancel,
+    /// Leave search input for normal mode (vim preset)
+    NormalMode,
+    /// Leave normal mode for search input (vim preset)
+    InsertMode,
+}
+
+impl Action {
+    /// All actions, in config/help order
+    pub const ALL: [Action; 37] = [
+        Action::Up,
+        Action::Down,
+        Action::PageUp,
+        Action::PageDown,
+        Action::First,
+        Action::Last,
+        Action::Switch,
+        Action::QuickCreate,
+        Action::Kill,
+        Action::Mark,
+        Action::MarkAll,
+        Action::Undo,
+        Action::Back,
+        Action::Exit,
+        Action::Reload,
+        Action::Rename,
+        Action::ToggleDead,
+        Action::Protect,
+        Action::Cleanup,
+        Action::ToggleGroup,
+        Action::Prune,
+        Action::PruneAll,
+        Action::Diagnostics,
+        Action::OpenTab,
+        Action::OpenTabLayout,
+        Action::OpenPane,
+        Action::OpenFloating,
+        Action::PickFolder,
+        Action::BrowseFolder,
+        Action::ClearFolder,
+        Action::EditFolder,
+        Action::ToggleHidden,
+        Action::EditName,
+        Action::Confirm,
+        Action::Cancel,
+        Action::NormalMode,
+        Action::InsertMode,
+    ];
+
+    /// Name used for this action in the `keybindings` config
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Switch => "switch",
+            Action::QuickCreate => "quick_create",
+            Action::Kill => "kill",
+            Action::Mark => "mark",
+            Action::MarkAll => "mark_all",
+            Action::Undo => "undo",
+            Action::Back => "back",
+            Action::Exit => "exit",
+            Action::Reload => "reload",
+            Action::Rename => "rename",
+            Action::ToggleDead => "toggle_dead",
+            Action::Protect => "protect",
+            Action::Cleanup => "cleanup",
+            Action::ToggleGroup => "toggle_group",
+            Action::Prune => "prune",
+            Action::PruneAll => "prune_all",
+            Action::Diagnostics => "diagnostics",
+            Action::OpenTab => "open_tab",
+            Action::OpenTabLayout => "open_tab_layout",
+            Action::OpenPane => "open_pane",
+            Action::OpenFloating => "open_floating",
+            Action::PickFolder => "pick_folder",
+            Action::BrowseFolder => "browse_folder",
+            Action::ClearFolder => "clear_folder",
+            Action::EditFolder => "edit_folder",
+            Action::ToggleHidden => "toggle_hidden",
+            Action::EditName => "edit_name",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::NormalMode => "normal_mode",
+            Action::InsertMode => "insert_mode",
+        }
+    }
+
+    /// Look up an action by its config name
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.config_name() == name)
+    }
+
+    /// Default key specs in insert mode (the only mode without a preset)
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["Up"],
+            Action::Down => &["Down"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::First => &["Home"],
+            Action::Last => &["End"],
+            Action::Switch => &["Enter"],
+            Action::QuickCreate => &["Ctrl Enter"],
+            Action::Kill => &["Delete"],
+            Action::Mark => &["Space"],
+            Action::MarkAll => &["Ctrl a"],
+            Action::Undo => &["Ctrl z"],
+            Action::Back => &["Esc"],
+            Action::Exit => &["Ctrl c"],
+            Action::Reload => &["Ctrl r"],
+            Action::Rename => &["Alt r"],
+            Action::ToggleDead => &["Alt d"],
+            Action::Protect => &["Alt v"],
+            Action::Cleanup => &["Alt c"],
+            Action::ToggleGroup => &["Tab"],
+            Action::Prune => &["Alt x"],
+            Action::PruneAll => &["Alt X"],
+            Action::Diagnostics => &["Alt s"],
+            Action::OpenTab => &["Alt t"],
+            Action::OpenTabLayout => &["Alt T"],
+            Action::OpenPane => &["Alt w"],
+            Action::OpenFloating => &["Alt W"],
+            Action::PickFolder => &["Ctrl f"],
+            Action::BrowseFolder => &["Ctrl d"],
+            Action::ClearFolder => &["Ctrl c"],
+            Action::EditFolder => &["Ctrl e"],
+            Action::ToggleHidden => &["Alt ."],
+            Action::EditName => &["Ctrl r"],
+            Action::Confirm => &["Enter"],
+            Action::Cancel => &["Esc"],
+            Action::NormalMode | Action::InsertMode => &[],
+        }
+    }
+
+    /// Key specs in normal mode of the vim preset
+    fn vim_normal_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Up => &["k", "Up"],
+            Action::Down => &["j", "Down"],
+            Action::PageUp => &["Ctrl u", "PageUp"],
+            Action::PageDown => &["Ctrl d", "Ctrl f", "PageDown"],
+            Action::First => &["g", "Home"],
+            Action::Last => &["G", "End"],
+            Action::Switch => &["Enter"],
+            Action::QuickCreate => &["Ctrl Enter"],
+            Action::Kill => &["x", "Delete"],
+            Action::Mark => &["Space"],
+            Action::MarkAll => &["Ctrl a"],
+            Action::Undo => &["u"],
+            Action::Back => &["Esc"],
+            Action::Exit => &["q", "Ctrl c"],
+            Action::Reload => &["R"],
+            Action::Rename => &["r"],
+            Action::ToggleDead => &["D"],
+            Action::Protect => &["p"],
+            Action::Cleanup => &["C"],
+            Action::ToggleGroup => &["z", "Tab"],
+            Action::Prune => &["Alt x"],
+            Action::PruneAll => &["Alt X"],
+            Action::Diagnostics => &["Alt s"],
+            Action::OpenTab => &["t"],
+            Action::OpenTabLayout => &["T"],
+            Action::OpenPane => &["w"],
+            Action::OpenFloating => &["W"],
+            Action::InsertMode => &["i", "/"],
+            _ => &[],
+        }
+    }
+}
+
+/// Keybinding preset selected with the `keybinding_preset` config
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Preset {
+    /// Typing always goes to the search bar
+    #[default]
+    Default,
+    /// Start in a normal mode with `j`/`k` navigation; `i` or `/` starts searching
+    Vim,
+}
+
+impl Preset {
+    /// Parse preset from config string (case-insensitive)
+    fn from_config_str(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "vim" => Preset::Vim,
+            _ => Preset::Default,
+        }
+    }
+}
+
+/// Keys bound to each action, for insert mode and (with the vim preset) normal mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    insert: BTreeMap<Action, Vec<KeyWithModifier>>,
+    normal: Option<BTreeMap<Action, Vec<KeyWithModifier>>>,
+    /// Config entries that could not be parsed
+    errors: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_preset(Preset::Default)
+    }
+}
+
+impl Keymap {
+    /// Build the keymap of a preset without overrides
+    fn from_preset(preset: Preset) -> Self {
+        let mut insert = build_map(Action::default_keys);
+        let normal = match preset {
+            Preset::Default => None,
+            Preset::Vim => {
+                // Esc leaves the search bar for normal mode instead of clearing it
+                insert.insert(Action::Back, Vec::new());
+                insert.insert(Action::NormalMode, parse_specs(&["Esc"]));
+                Some(build_map(Action::vim_normal_keys))
+            }
+        };
+        Self {
+            insert,
+            normal,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Build the keymap from the `keybinding_preset`, `keybindings` and `normal_keybindings` config
+    pub fn from_config(
+        preset: Option<&str>,
+        bindings: Option<&str>,
+        normal_bindings: Option<&str>,
+    ) -> Self {
+        let preset = preset.map(Preset::from_config_str).unwrap_or_default();
+        let mut keymap = Self::from_preset(preset);
+
+        if let Some(bindings) = bindings {
+            keymap
+                .errors
+                .extend(apply_overrides(&mut keymap.insert, bindings));
+        }
+        if let Some(normal_bindings) = normal_bindings {
+            match keymap.normal.as_mut() {
+                Some(normal) => keymap
+                    .errors
+                    .extend(apply_overrides(normal, normal_bindings)),
+                None => keymap
+                    .errors
+                    .push("normal_keybindings requires keybinding_preset \"vim\"".to_string()),
+            }
+        }
+        keymap
+    }
+
+    /// Whether the keymap has a normal mode (vim preset)
+    pub fn has_normal_mode(&self) -> bool {
+        self.normal.is_some()
+    }
+
+    /// Config entries that could not be parsed
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Keys bound to an action in the given mode
+    pub fn keys(&self, action: Action, normal_mode: bool) -> &[KeyWithModifier] {
+        let map = match (&self.normal, normal_mode) {
+            (Some(normal), true) => normal,
+            _ => &self.insert,
+        };
+        map.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Whether a key triggers an action in the given mode
+    pub fn matches(&self, action: Action, key: &KeyWithModifier, normal_mode: bool) -> bool {
+        self.keys(action, normal_mode).contains(key)
+    }
+
+    /// First of the candidate actions the key triggers in the given mode
+    pub fn action_for(
+        &self,
+        key: &KeyWithModifier,
+        candidates: &[Action],
+        normal_mode: bool,
+    ) -> Option<Action> {
+        candidates
+            .iter()
+            .copied()
+            .find(|action| self.matches(*action, key, normal_mode))
+    }
+
+    /// Action for a key on the main screen; once a search term is typed (outside of normal
+    /// mode), keys that edit it go to the search bar instead of their bindings
+    pub fn main_action_for(
+        &self,
+        key: &KeyWithModifier,
+        candidates: &[Action],
+        normal_mode: bool,
+        search_typed: bool,
+    ) -> Option<Action> {
+        if search_typed && !normal_mode && edits_search_term(key) {
+            return None;
+        }
+        self.action_for(key, candidates, normal_mode)
+    }
+
+    /// Help label of the first key bound to an action, e.g. `Ctrl+r`
+    pub fn label(&self, action: Action, normal_mode: bool) -> Option<String> {
+        self.keys(action, normal_mode).first().map(key_label)
+    }
+}
+
+/// Keys that edit a typed search term even when bound to an action
+/// (Space is typed, Home/End move the cursor)
+fn edits_search_term(key: &KeyWithModifier) -> bool {
+    key.has_no_modifiers()
+        && matches!(
+            key.bare_key,
+            BareKey::Char(' ') | BareKey::Home | BareKey::End
+        )
+}
+
+/// Build an action map from a per-action key spec table
+fn build_map(
+    keys_of: fn(&Action) -> &'static [&'static str],
+) -> BTreeMap<Action, Vec<KeyWithModifier>> {
+    Action::ALL
+        .iter()
+        .map(|action| (*action, parse_specs(keys_of(action))))
+        .collect()
+}
+
+/// Parse built-in key specs
+fn parse_specs(specs: &[&str]) -> Vec<KeyWithModifier> {
+    specs
+        .iter()
+        .filter_map(|spec| KeyWithModifier::from_str(spec).ok())
+        .collect()
+}
+
+/// Apply `action=Key spec, Key spec|...` overrides, returning entries that could not be parsed
+fn apply_overrides(
+    map: &mut BTreeMap<Action, Vec<KeyWithModifier>>,
+    bindings: &str,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for entry in bindings.split('|').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((name, specs)) = entry.split_once('=') else {
+            errors.push(format!("Invalid keybinding '{}'", entry));
+            continue;
+        };
+        let Some(action) = Action::from_config_name(name.trim()) else {
+            errors.push(format!("Unknown keybinding action '{}'", name.trim()));
+            continue;
+        };
+
+        let mut keys = Vec::new();
+        for spec in specs.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match KeyWithModifier::from_str(spec) {
+                Ok(key) => keys.push(key),
+                Err(_) => errors.push(format!("Invalid key '{}' for '{}'", spec, name.trim())),
+            }
+        }
+        // An empty spec list unbinds the action
+        map.insert(action, keys);
+    }
+    errors
+}
+
+/// Help label of a key, e.g. `Ctrl+Enter` or `↑`
+pub fn key_label(key: &KeyWithModifier) -> String {
+    let bare = match key.bare_key {
+        BareKey::PageDown => "PgDn".to_string(),
+        BareKey::PageUp => "PgUp".to_string(),
+        BareKey::Left => "←".to_string(),
+        BareKey::Down => "↓".to_string(),
+        BareKey::Up => "↑".to_string(),
+        BareKey::Right => "→".to_string(),
+        BareKey::Home => "Home".to_string(),
+        BareKey::End => "End".to_string(),
+        BareKey::Backspace => "Backspace".to_string(),
+        BareKey::Delete => "Del".to_string(),
+        BareKey::Insert => "Ins".to_string(),
+        BareKey::F(index) => format!("F{}", index),
+        BareKey::Char(' ') => "Space".to_string(),
+        BareKey::Char(c) => c.to_string(),
+        BareKey::Tab => "Tab".to_string(),
+        BareKey::Esc => "Esc".to_string(),
+        BareKey::Enter => "Enter".to_string(),
+        other => other.to_string(),
+    };
+    key.key_modifiers
+        .iter()
+        .map(|modifier| modifier.to_string())
+        .chain(std::iter::once(bare))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(spec: &str) -> KeyWithModifier {
+        KeyWithModifier::from_str(spec).unwrap()
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert!(!keymap.has_normal_mode());
+        assert!(keymap.matches(Action::Reload, &key("Ctrl r"), false));
+        assert!(keymap.matches(Action::Rename, &key("Alt r"), false));
+        assert!(!keymap.matches(Action::Rename, &key("r"), false));
+        assert!(keymap.matches(Action::ToggleHidden, &key("Alt ."), false));
+        assert!(keymap.matches(Action::BrowseFolder, &key("Ctrl d"), false));
+        assert_eq!(
+            keymap.label(Action::QuickCreate, false).as_deref(),
+            Some("Ctrl+Enter")
+        );
+    }
+
+    #[test]
+    fn test_space_is_typed_into_search() {
+        let keymap = Keymap::default();
+        let mut search = crate::ui::TextInput::default();
+        for c in "web api".chars() {
+            let typed = KeyWithModifier::new(BareKey::Char(c));
+            if keymap
+                .main_action_for(&typed, &Action::ALL, false, !search.is_empty())
+                .is_none()
+            {
+                search.handle_key(&typed);
+            }
+        }
+        assert_eq!(search.text(), "web api");
+        // Space marks while nothing is typed
+        assert_eq!(
+            keymap.main_action_for(&key("Space"), &Action::ALL, false, false),
+            Some(Action::Mark)
+        );
+    }
+
+    #[test]
+    fn test_home_and_end_move_the_search_cursor() {
+        let keymap = Keymap::default();
+        let mut search = crate::ui::TextInput::default();
+        search.set_text("web");
+        for spec in ["Home", "End"] {
+            assert_eq!(
+                keymap.main_action_for(&key(spec), &Action::ALL, false, true),
+                None
+            );
+        }
+        search.handle_key(&key("Home"));
+        search.handle_key(&key("x"));
+        assert_eq!(search.text(), "xweb");
+        // They select the first and last item while nothing is typed
+        assert_eq!(
+            keymap.main_action_for(&key("Home"), &Action::ALL, false, false),
+            Some(Action::First)
+        );
+        assert_eq!(
+            keymap.main_action_for(&key("End"), &Action::ALL, false, false),
+            Some(Action::Last)
+        );
+    }
+
+    #[test]
+    fn test_overrides_replace_and_unbind() {
+        let keymap = Keymap::from_config(None, Some("rename=Alt n, F2 | reload="), None);
+        assert!(keymap.errors().is_empty());
+        assert!(keymap.matches(Action::Rename, &key("Alt n"), false));
+        assert!(keymap.matches(Action::Rename, &key("F2"), false));
+        assert!(!keymap.matches(Action::Rename, &key("Alt r"), false));
+        assert!(keymap.keys(Action::Reload, false).is_empty());
+        // Untouched actions keep their defaults
+        assert!(keymap.matches(Action::Kill, &key("Delete"), false));
+    }
+
+    #[test]
+    fn test_invalid_entries_are_reported() {
+        let keymap = Keymap::from_config(None, Some("teleport=Alt t|kill=Hyper x|undo"), None);
+        assert_eq!(keymap.errors().len(), 3);
+        // Actions with only invalid keys end up unbound
+        assert!(keymap.keys(Action::Kill, false).is_empty());
+    }
+
+    #[test]
+    fn test_vim_preset_modes() {
+        let keymap = Keymap::from_config(Some("vim"), None, Some("kill=d"));
+        assert!(keymap.has_normal_mode());
+        assert!(keymap.matches(Action::Down, &key("j"), true));
+        assert!(!keymap.matches(Action::Down, &key("j"), false));
+        assert!(keymap.matches(Action::NormalMode, &key("Esc"), false));
+        assert!(!keymap.matches(Action::Back, &key("Esc"), false));
+        assert!(keymap.matches(Action::Kill, &key("d"), true));
+        // Uppercase specs match shifted key presses
+        assert!(keymap.matches(Action::Reload, &key("Shift r"), true));
+    }
+
+    #[test]
+    fn test_normal_bindings_require_vim_preset() {
+        let keymap = Keymap::from_config(None, None, Some("kill=d"));
+        assert_eq!(keymap.errors().len(), 1);
+    }
+}
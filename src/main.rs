@@ -1,27 +1,46 @@
+mod browser;
 mod config;
+mod keybindings;
 mod new_session_info;
+mod path_entry;
+mod pattern;
 mod session;
 mod state;
+mod tab_layout;
 mod ui;
 mod zoxide;
 
-use state::PluginState;
+use session::grouping::base_path_of;
+use state::{ActiveScreen, PluginState};
 use std::collections::BTreeMap;
 use ui::PluginRenderer;
 use zellij_tile::prelude::*;
 
 register_plugin!(PluginState);
 
+/// Where the host filesystem root is mapped inside the plugin
+const HOST_ROOT: &str = "/host";
+
+/// Processed directories of the last run, shown on startup until the sources answer
+/// (in the plugin's own /tmp folder, not the host's)
+const DIRECTORY_CACHE: &str = "/tmp/zsm-directory-cache";
+
 impl ZellijPlugin for PluginState {
     fn load(&mut self, configuration: BTreeMap<String, String>) {
         self.initialize(configuration);
 
+        // The plugin's own /tmp is readable before permissions are granted
+        if let Ok(text) = std::fs::read_to_string(DIRECTORY_CACHE) {
+            self.load_cached_directories(zoxide::cache::deserialize(&text));
+        }
+
         // Request permissions - same as session-manager
         request_permission(&[
             PermissionType::RunCommands,                  // run zoxide command
             PermissionType::ReadApplicationState,         // read current sessions/layouts
             PermissionType::ChangeApplicationState,       // create and switch sessions
             PermissionType::MessageAndLaunchOtherPlugins, // launch filepicker plugin
+            PermissionType::FullHdAccess,                 // check zoxide directories still exist
         ]);
 
         subscribe(&[
@@ -30,6 +49,8 @@ impl ZellijPlugin for PluginState {
             EventType::Key,
             EventType::RunCommandResult,
             EventType::PermissionRequestResult,
+            EventType::Timer,
+            EventType::Mouse,
         ]);
 
         // Don't fetch zoxide directories immediately - wait for permissions
@@ -47,6 +68,12 @@ impl ZellijPlugin for PluginState {
             Event::Key(key) => {
                 should_render = self.handle_key(key);
             }
+            Event::Mouse(mouse) => {
+                should_render = self.handle_mouse(mouse);
+            }
+            Event::Timer(_) => {
+                should_render = self.handle_timer();
+            }
             Event::PermissionRequestResult(permission_status) => {
                 match permission_status {
                     PermissionStatus::Granted => {
@@ -57,11 +84,20 @@ impl ZellijPlugin for PluginState {
                             "ZSM - Zoxide Session Manager".to_string(),
                         );
 
-                        // Now that we have permissions, fetch zoxide directories
-                        self.fetch_zoxide_directories();
+                        // Map the host root so zoxide directories can be checked for existence
+                        change_host_folder(std::path::PathBuf::from("/"));
+
+                        // Dead sessions deferred by an instance unloaded during its undo window
+                        self.finalize_abandoned_deletions();
+
+                        // Now that we have permissions, fetch directories
+                        self.fetch_directories();
+                        self.schedule_refresh();
+                        self.request_home_dir();
                         should_render = true;
                     }
                     PermissionStatus::Denied => {
+                        self.mark_directories_loaded();
                         self.set_error(
                             "RunCommands permission denied - cannot fetch zoxide directories"
                                 .to_string(),
@@ -75,22 +111,61 @@ impl ZellijPlugin for PluginState {
                 let sessions_changed = self.update_sessions(session_infos);
                 let resurrectable_changed =
                     self.update_resurrectable_sessions(resurrectable_session_infos);
-                should_render = sessions_changed || resurrectable_changed;
+                let cleanup_changed = self.apply_cleanup_policy();
+                should_render = sessions_changed || resurrectable_changed || cleanup_changed;
             }
             Event::RunCommandResult(exit_code, stdout, stderr, context) => {
-                if context.contains_key("zoxide_query") {
+                if let Some(index) = context
+                    .get("zsm_source")
+                    .and_then(|index| index.parse::<usize>().ok())
+                {
+                    let command = context.get("zsm_command").cloned().unwrap_or_default();
                     if exit_code == Some(0) {
-                        let stdout_str = String::from_utf8_lossy(&stdout);
-                        self.process_zoxide_output(&stdout_str);
-                        should_render = true;
+                        self.process_source_output(index, command, &stdout);
+                    } else {
+                        // Sources are reread on every refresh; one that keeps failing is only
+                        // reported when it starts failing, the diagnostics screen shows the rest
+                        let error = describe_failure(&context, exit_code, &stderr);
+                        if self.set_source_error(index, command, error.clone()) {
+                            let name = self.config().directory_sources[index].name();
+                            self.set_error(format!(
+                                "Failed to read directories from {}: {}",
+                                name, error
+                            ));
+                        }
+                    }
+                    // Cached directories stay listed until every source has answered,
+                    // so one fast source doesn't replace the full list with its own
+                    if self.record_source_answer(index) {
+                        self.show_source_directories();
+                    }
+                    should_render = true;
+                } else if let Some(term) = context.get("zsm_disk_search") {
+                    // find exits non-zero when some directories are unreadable, keep what it printed
+                    let stdout_str = String::from_utf8_lossy(&stdout);
+                    self.add_disk_search_results(term, &stdout_str);
+                    should_render = true;
+                } else if let Some(query) = context.get("zsm_query_create") {
+                    // Ignore answers for a search the user has changed or left since
+                    if self.active_screen() != ActiveScreen::Main
+                        || self.search_engine().search_term().trim() != query
+                    {
+                        return false;
+                    }
+                    let stdout_str = String::from_utf8_lossy(&stdout);
+                    let path = stdout_str.trim_end_matches(['\r', '\n']);
+                    if exit_code == Some(0) && !path.is_empty() {
+                        self.create_session_in_directory(path);
                     } else {
-                        let stderr_str = String::from_utf8_lossy(&stderr);
                         self.set_error(format!(
-                            "Failed to run zoxide (is it installed?): {}",
-                            stderr_str
+                            "No zoxide directory matches '{}': {}",
+                            query,
+                            describe_failure(&context, exit_code, &stderr)
                         ));
-                        should_render = true;
                     }
+                    should_render = true;
+                } else if context.contains_key("zsm_read_home") {
+                    self.set_home_dir(&String::from_utf8_lossy(&stdout));
                 } else if context.contains_key("zsm_read_previous") {
                     let stdout_str = String::from_utf8_lossy(&stdout);
                     let previous = stdout_str.trim().to_string();
@@ -102,6 +177,51 @@ impl ZellijPlugin for PluginState {
                     let stdout_str = String::from_utf8_lossy(&stdout);
                     self.set_mru_timestamps(&stdout_str);
                     should_render = true;
+                } else if context.contains_key("zsm_read_directories") {
+                    let stdout_str = String::from_utf8_lossy(&stdout);
+                    self.set_session_directories(&stdout_str);
+                    should_render = true;
+                } else if context.contains_key("zsm_zoxide_remove") {
+                    if exit_code != Some(0) {
+                        self.set_error(format!(
+                            "Failed to remove directories from zoxide: {}",
+                            describe_failure(&context, exit_code, &stderr)
+                        ));
+                        // Bring back the directories that are still in the database
+                        self.fetch_directories();
+                        should_render = true;
+                    }
+                } else if context.contains_key("zsm_zoxide_add") {
+                    if exit_code != Some(0) {
+                        self.set_error(format!(
+                            "Failed to add directory to zoxide: {}",
+                            describe_failure(&context, exit_code, &stderr)
+                        ));
+                        should_render = true;
+                    }
+                } else if context.contains_key("zsm_read_git_roots") {
+                    let stdout_str = String::from_utf8_lossy(&stdout);
+                    self.set_git_roots(&stdout_str);
+                    should_render = true;
+                } else if context.contains_key("zsm_read_collapsed") {
+                    let stdout_str = String::from_utf8_lossy(&stdout);
+                    self.set_collapsed_groups(&stdout_str);
+                    should_render = true;
+                } else if let Some(old_name) = context.get("zsm_rename") {
+                    if exit_code == Some(0) {
+                        if let Some(new_name) = context.get("zsm_rename_to") {
+                            self.finish_rename(old_name, new_name);
+                            should_render = true;
+                        }
+                    } else {
+                        let stderr_str = String::from_utf8_lossy(&stderr);
+                        self.set_error(format!(
+                            "Failed to rename session '{}': {}",
+                            old_name,
+                            stderr_str.trim()
+                        ));
+                        should_render = true;
+                    }
                 }
             }
             _ => (),
@@ -162,38 +282,72 @@ impl ZellijPlugin for PluginState {
     }
 }
 
+/// Describe a failed command for the error line: the exact command line, its exit status and stderr
+fn describe_failure(
+    context: &BTreeMap<String, String>,
+    exit_code: Option<i32>,
+    stderr: &[u8],
+) -> String {
+    let command = context
+        .get("zsm_command")
+        .map(String::as_str)
+        .unwrap_or("command");
+    let status = match exit_code {
+        Some(code) => format!("exit code {}", code),
+        None => "could not be started, is it installed?".to_string(),
+    };
+    format!(
+        "`{}` ({}): {}",
+        command,
+        status,
+        String::from_utf8_lossy(stderr).trim()
+    )
+}
+
 impl PluginState {
-    fn fetch_zoxide_directories(&mut self) {
-        let mut context = BTreeMap::new();
-        context.insert("zoxide_query".to_string(), "true".to_string());
-        run_command(&["zoxide", "query", "-l", "-s"], context);
+    /// Run the command of every configured directory source
+    fn fetch_directories(&mut self) {
+        if self.config().directory_sources.is_empty() {
+            self.mark_directories_loaded();
+        }
+        for (index, source) in self.config().directory_sources.iter().enumerate() {
+            let command = source.command(&self.config().zoxide_command);
+            let env = source.env(&self.config().zoxide_command);
+            let mut context = BTreeMap::new();
+            context.insert("zsm_source".to_string(), index.to_string());
+            context.insert(
+                "zsm_command".to_string(),
+                zoxide::display_command(&command, &env),
+            );
+            let command: Vec<&str> = command.iter().map(String::as_str).collect();
+            run_command_with_env_variables_and_cwd(
+                &command,
+                env,
+                std::path::PathBuf::from("."),
+                context,
+            );
+        }
     }
 
-    fn process_zoxide_output(&mut self, output: &str) {
-        let mut directories = Vec::new();
-
-        for line in output.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
+    /// Read the home directory of the host user (the directory browser starts there)
+    fn request_home_dir(&self) {
+        let mut context = BTreeMap::new();
+        context.insert("zsm_read_home".to_string(), String::new());
+        run_command(&["sh", "-c", "printf %s \"$HOME\""], context);
+    }
 
-            // zoxide output format: "score path"
-            let parts: Vec<&str> = line.trim().splitn(2, ' ').collect();
-            if parts.len() == 2 {
-                if let Ok(score) = parts[0].parse::<f64>() {
-                    let path = parts[1];
-
-                    directories.push(zoxide::ZoxideDirectory {
-                        ranking: score,
-                        directory: path.to_string(),
-                        session_name: String::new(), // Will be set by smart naming
-                    });
-                }
-            }
-        }
+    /// Parse the output of a directory source and store it with its diagnostics
+    fn process_source_output(&mut self, index: usize, command: String, output: &[u8]) {
+        let Some(source) = self.config().directory_sources.get(index) else {
+            return;
+        };
+        let result = source.parse_output(output);
+        self.set_source_output(index, command, result);
+    }
 
-        // Generate smart session names before sorting
-        self.generate_smart_session_names(&mut directories);
+    /// List the merged directories of all sources and cache them for the next startup
+    fn show_source_directories(&mut self) {
+        let mut directories = self.merged_source_directories();
 
         // Sort by score in descending order (higher scores first)
         directories.sort_by(|a, b| {
@@ -202,6 +356,22 @@ impl PluginState {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        // Drop excluded and low-scoring directories before naming,
+        // so they don't add context to the names of the remaining ones
+        self.config().directory_filter.apply(&mut directories);
+
+        // Generate smart session names
+        self.generate_smart_session_names(&mut directories);
+
+        // The host root is mapped to /host once permissions are granted
+        zoxide::mark_vanished(&mut directories, |path| {
+            std::path::Path::new(HOST_ROOT)
+                .join(path.trim_start_matches('/'))
+                .exists()
+        });
+
+        // Best effort: without a cache the next startup shows "Loading…" instead
+        let _ = std::fs::write(DIRECTORY_CACHE, zoxide::cache::serialize(&directories));
         self.update_zoxide_directories(directories);
     }
 
@@ -359,43 +529,10 @@ impl PluginState {
     }
 
     fn normalize_path(&self, path: &str) -> String {
-        let base_paths = &self.config().base_paths;
-
-        // If no base paths configured, return the original path
-        if base_paths.is_empty() {
-            return path.to_string();
-        }
-
-        // Find the longest matching base path
-        let mut longest_match: Option<&String> = None;
-        let mut longest_match_len = 0;
-
-        for base_path in base_paths {
-            // Normalize base path (remove trailing slash)
-            let normalized_base = base_path.trim_end_matches('/');
-
-            // Check if path starts with this base path
-            if path.starts_with(normalized_base) {
-                // Make sure it's a directory boundary (not partial match)
-                let is_directory_boundary = path.len() == normalized_base.len()
-                    || path.chars().nth(normalized_base.len()) == Some('/');
-                if is_directory_boundary && normalized_base.len() > longest_match_len {
-                    longest_match = Some(base_path);
-                    longest_match_len = normalized_base.len();
-                }
-            }
-        }
-
-        if let Some(base_path) = longest_match {
-            let normalized_base = base_path.trim_end_matches('/');
-
-            // If path exactly matches the base path, keep the full path
-            if path == normalized_base {
-                return path.to_string();
-            }
-
-            // Strip the base path and the following slash
-            if let Some(stripped) = path.strip_prefix(normalized_base) {
+        // Strip the longest matching base path and the following slash
+        // If path exactly matches the base path, keep the full path
+        if let Some(base_path) = base_path_of(path, &self.config().base_paths) {
+            if let Some(stripped) = path.strip_prefix(base_path) {
                 let stripped = stripped.strip_prefix('/').unwrap_or(stripped);
                 if !stripped.is_empty() {
                     return stripped.to_string();
@@ -3,12 +3,17 @@ use fuzzy_matcher::FuzzyMatcher;
 use std::path::PathBuf;
 use zellij_tile::prelude::*;
 
+use crate::path_entry::PathEntry;
+use crate::ui::TextInput;
+
 #[derive(Default)]
 pub struct NewSessionInfo {
-    name: String,
+    name: TextInput,
     layout_list: LayoutList,
     entering_new_session_info: EnteringState,
     pub new_session_folder: Option<PathBuf>,
+    /// Folder path being typed, while editing it
+    pub folder_entry: Option<PathEntry>,
 }
 
 #[derive(Default, Eq, PartialEq)]
@@ -20,11 +25,15 @@ enum EnteringState {
 
 impl NewSessionInfo {
     pub fn name(&self) -> &str {
+        self.name.text()
+    }
+
+    pub fn name_input(&self) -> &TextInput {
         &self.name
     }
 
     pub fn set_name(&mut self, name: &str) {
-        self.name = name.to_string();
+        self.name.set_text(name);
     }
 
     pub fn set_folder(&mut self, folder: Option<PathBuf>) {
@@ -35,6 +44,10 @@ impl NewSessionInfo {
         self.new_session_folder.as_ref()
     }
 
+    pub fn folder_entry(&self) -> Option<&PathEntry> {
+        self.folder_entry.as_ref()
+    }
+
     pub fn advance_to_layout_selection(&mut self) {
         self.entering_new_session_info = EnteringState::EnteringLayoutSearch;
     }
@@ -57,7 +70,7 @@ impl NewSessionInfo {
     pub fn add_char(&mut self, character: char) {
         match self.entering_new_session_info {
             EnteringState::EnteringName => {
-                self.name.push(character);
+                self.name.insert_str(&character.to_string());
             }
             EnteringState::EnteringLayoutSearch => {
                 self.layout_list.layout_search_term.push(character);
@@ -68,7 +81,8 @@ impl NewSessionInfo {
     pub fn handle_backspace(&mut self) {
         match self.entering_new_session_info {
             EnteringState::EnteringName => {
-                self.name.pop();
+                self.name
+                    .handle_key(&KeyWithModifier::new(BareKey::Backspace));
             }
             EnteringState::EnteringLayoutSearch => {
                 self.layout_list.layout_search_term.pop();
@@ -88,7 +102,32 @@ impl NewSessionInfo {
             }
         }
     }
+    /// Go back to previous state or clear current input
+    pub fn handle_escape(&mut self) {
+        match self.entering_new_session_info {
+            EnteringState::EnteringLayoutSearch => {
+                // In layout search, if there's a search term, clear it; otherwise go back to name entry
+                if !self.layout_list.layout_search_term.is_empty() {
+                    self.layout_list.layout_search_term.clear();
+                    self.update_layout_search_term();
+                } else {
+                    // No search term, go back to name entry
+                    self.entering_new_session_info = EnteringState::EnteringName;
+                }
+            }
+            EnteringState::EnteringName => {
+                // In name entry, clear the name
+                self.name.clear();
+            }
+        }
+    }
     pub fn handle_key(&mut self, key: KeyWithModifier) {
+        // Line editing keys go to the name input while it has focus
+        if self.entering_new_session_info == EnteringState::EnteringName
+            && self.name.handle_key(&key)
+        {
+            return;
+        }
         match key.bare_key {
             BareKey::Backspace if key.has_no_modifiers() => {
                 self.handle_backspace();
@@ -96,30 +135,8 @@ impl NewSessionInfo {
             BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
                 self.handle_break();
             }
-            BareKey::Char('r') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                // Ctrl+R to correct session name - only when in layout search mode
-                if self.entering_new_session_info == EnteringState::EnteringLayoutSearch {
-                    self.correct_session_name();
-                }
-            }
             BareKey::Esc if key.has_no_modifiers() => {
-                // Esc goes back to previous state or clears current input
-                match self.entering_new_session_info {
-                    EnteringState::EnteringLayoutSearch => {
-                        // In layout search, if there's a search term, clear it; otherwise go back to name entry
-                        if !self.layout_list.layout_search_term.is_empty() {
-                            self.layout_list.layout_search_term.clear();
-                            self.update_layout_search_term();
-                        } else {
-                            // No search term, go back to name entry
-                            self.entering_new_session_info = EnteringState::EnteringName;
-                        }
-                    }
-                    EnteringState::EnteringName => {
-                        // In name entry, clear the name
-                        self.name.clear();
-                    }
-                }
+                self.handle_escape();
             }
             BareKey::Char(character) if key.has_no_modifiers() => {
                 self.add_char(character);
@@ -142,7 +159,7 @@ impl NewSessionInfo {
         let new_session_name = if self.name.is_empty() {
             None
         } else {
-            Some(self.name.as_str())
+            Some(self.name.text())
         };
 
         if new_session_name != current_session_name.as_ref().map(|s| s.as_str()) {
@@ -188,7 +205,7 @@ impl NewSessionInfo {
                 let new_session_name = if self.name.is_empty() {
                     None
                 } else {
-                    Some(self.name.as_str())
+                    Some(self.name.text())
                 };
                 if new_session_name != current_session_name.as_ref().map(|s| s.as_str()) {
                     match new_session_layout {
@@ -315,15 +332,32 @@ impl NewSessionInfo {
                     });
                 }
             }
-            matches.sort_by(|a, b| b.score.cmp(&a.score));
+            matches.sort_by_key(|m| std::cmp::Reverse(m.score));
             self.layout_list.layout_search_results = matches;
             self.layout_list.clear_selection();
         }
     }
-    fn move_selection_up(&mut self) {
+    /// Index range of the layouts rendered in `max_rows` rows
+    pub fn layout_render_range(&self, max_rows: usize) -> (usize, usize) {
+        let len = if self.is_searching() {
+            self.layout_list.layout_search_results.len()
+        } else {
+            self.layout_count()
+        };
+        let (first, last) =
+            self.range_to_render(max_rows, len, Some(self.layout_list.selected_layout_index));
+        (first, last.min(len))
+    }
+    pub fn selected_layout_index(&self) -> usize {
+        self.layout_list.selected_layout_index
+    }
+    pub fn select_layout(&mut self, index: usize) {
+        self.layout_list.selected_layout_index = index.min(self.layout_list.max_index());
+    }
+    pub fn move_selection_up(&mut self) {
         self.layout_list.move_selection_up();
     }
-    fn move_selection_down(&mut self) {
+    pub fn move_selection_down(&mut self) {
         self.layout_list.move_selection_down();
     }
 }
@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use crate::ui::TextInput;
+
+/// Most suggestions listed below the folder path being typed
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Folder path typed on the new session screen, with completions and zoxide suggestions
+#[derive(Debug, Default, Clone)]
+pub struct PathEntry {
+    /// Typed path, `~` is expanded when it is used
+    input: TextInput,
+    /// Completion candidates or zoxide directories matching the typed path
+    suggestions: Vec<String>,
+    /// Selected suggestion, if any
+    selected: Option<usize>,
+}
+
+impl PathEntry {
+    /// Start editing with an initial path
+    pub fn new(path: &str) -> Self {
+        let mut input = TextInput::default();
+        input.set_text(path);
+        Self {
+            input,
+            ..Default::default()
+        }
+    }
+
+    pub fn input(&self) -> &TextInput {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut TextInput {
+        &mut self.input
+    }
+
+    /// Replace the typed path, keeping the suggestions
+    pub fn set_text(&mut self, text: &str) {
+        self.input.set_text(text);
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Replace the suggestions, clearing the selection
+    pub fn set_suggestions(&mut self, suggestions: Vec<String>) {
+        self.suggestions = suggestions;
+        self.selected = None;
+    }
+
+    /// Select the previous suggestion (none above the first)
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.and_then(|index| index.checked_sub(1));
+    }
+
+    /// Select the next suggestion
+    pub fn move_down(&mut self) {
+        if !self.suggestions.is_empty() {
+            self.selected = Some(
+                self.selected
+                    .map_or(0, |index| (index + 1).min(self.suggestions.len() - 1)),
+            );
+        }
+    }
+
+    /// The selected suggestion, or else the typed path
+    pub fn chosen(&self) -> &str {
+        self.selected
+            .and_then(|index| self.suggestions.get(index))
+            .map_or(self.input.text(), String::as_str)
+    }
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_tilde(path: &str, home: Option<&Path>) -> String {
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home.to_string_lossy().trim_end_matches('/'), rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Complete the last component of a typed path with the subdirectories of its parent
+/// `list` returns the subdirectory names of an (expanded) directory
+/// Returns the completed text and, if the completion is ambiguous, the candidates
+pub fn complete(
+    typed: &str,
+    home: Option<&Path>,
+    list: impl Fn(&str) -> Vec<String>,
+) -> (String, Vec<String>) {
+    // "~" alone completes like "~/"
+    let typed = if typed == "~" { "~/" } else { typed };
+    let Some(split) = typed.rfind('/') else {
+        return (typed.to_string(), Vec::new());
+    };
+    let (directory, prefix) = typed.split_at(split + 1);
+    let expanded = expand_tilde(directory, home);
+    if !expanded.starts_with('/') {
+        return (typed.to_string(), Vec::new());
+    }
+
+    let mut matches: Vec<String> = list(&expanded)
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        // Hidden directories only complete once a '.' is typed
+        .filter(|name| prefix.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    matches.sort();
+    match matches.as_slice() {
+        [] => (typed.to_string(), Vec::new()),
+        [name] => (format!("{}{}/", directory, name), Vec::new()),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, name| {
+                let len = common
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((i, a), _)| i + a.len_utf8());
+                &common[..len]
+            });
+            let candidates = matches
+                .iter()
+                .map(|name| format!("{}{}/", directory, name))
+                .collect();
+            (format!("{}{}", directory, common), candidates)
+        }
+    }
+}
+
+/// Directories containing the typed path (case-insensitive), in the given (zoxide) order
+pub fn suggestions<'a>(
+    typed: &str,
+    home: Option<&Path>,
+    directories: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let needle = expand_tilde(typed.trim(), home).to_lowercase();
+    let needle = needle.trim_end_matches('/');
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    directories
+        .into_iter()
+        .filter(|dir| dir.to_lowercase().contains(needle) && dir.trim_end_matches('/') != needle)
+        .take(MAX_SUGGESTIONS)
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(directory: &str) -> Vec<String> {
+        let names: &[&str] = match directory {
+            "/home/u/" => &["projects", "photos", ".config", "notes"],
+            "/home/u/projects/" => &["api"],
+            _ => &[],
+        };
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let home = Some(Path::new("/home/u"));
+        assert_eq!(complete("~", home, list).0, "~/");
+        assert_eq!(
+            complete("~/pr", home, list),
+            ("~/projects/".to_string(), Vec::new())
+        );
+        assert_eq!(
+            complete("/home/u/p", home, list),
+            (
+                "/home/u/p".to_string(),
+                vec![
+                    "/home/u/photos/".to_string(),
+                    "/home/u/projects/".to_string()
+                ]
+            )
+        );
+        assert_eq!(
+            complete("~/.c", home, list),
+            ("~/.config/".to_string(), Vec::new())
+        );
+        // Relative paths are not completed
+        assert_eq!(complete("pro", home, list), ("pro".to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn test_suggestions_follow_directory_order() {
+        let home = Some(Path::new("/home/u"));
+        let dirs = ["/home/u/work/API", "/srv/api", "/home/u/api"];
+        assert_eq!(
+            suggestions("api", home, dirs),
+            vec!["/home/u/work/API", "/srv/api", "/home/u/api"]
+        );
+        assert_eq!(suggestions("~/api", home, dirs), Vec::<String>::new());
+        assert_eq!(
+            suggestions("~/", home, dirs),
+            vec!["/home/u/work/API", "/home/u/api"]
+        );
+    }
+
+    #[test]
+    fn test_chosen_suggestion() {
+        let mut entry = PathEntry::new("~/a");
+        entry.set_suggestions(vec!["/a".to_string(), "/b".to_string()]);
+        assert_eq!(entry.chosen(), "~/a");
+        entry.move_down();
+        entry.move_down();
+        entry.move_down();
+        assert_eq!(entry.chosen(), "/b");
+        entry.move_up();
+        entry.move_up();
+        assert_eq!(entry.chosen(), "~/a");
+    }
+}
@@ -0,0 +1,114 @@
+/// Match text against a glob pattern supporting `*` (any run of characters) and `?` (one character)
+/// Patterns without wildcards must match exactly
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last '*' in the pattern and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last '*' swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    // Trailing '*'s match the empty string
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Check whether text matches any of the given glob patterns
+pub fn matches_any(patterns: &[String], text: &str) -> bool {
+    patterns.iter().any(|pattern| glob_match(pattern, text))
+}
+
+/// A path pattern from the config: a glob, or a regex when prefixed with `re:`
+#[derive(Debug, Clone)]
+pub enum PathPattern {
+    /// Glob matched against the whole path (`*` also matches `/`)
+    Glob(String),
+    /// Regex searched anywhere in the path
+    Regex(regex::Regex),
+}
+
+impl PathPattern {
+    /// Parse a config entry, failing on invalid regexes
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        match pattern.strip_prefix("re:") {
+            Some(expr) => regex::Regex::new(expr)
+                .map(PathPattern::Regex)
+                .map_err(|err| format!("Invalid path regex '{}': {}", expr, err)),
+            None => Ok(PathPattern::Glob(pattern.to_string())),
+        }
+    }
+
+    /// Check whether a path matches this pattern
+    pub fn is_match(&self, path: &str) -> bool {
+        match self {
+            PathPattern::Glob(glob) => glob_match(glob, path),
+            PathPattern::Regex(regex) => regex.is_match(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_pattern_requires_exact_match() {
+        assert!(glob_match("infra", "infra"));
+        assert!(!glob_match("infra", "infra.2"));
+        assert!(!glob_match("infra", "inf"));
+    }
+
+    #[test]
+    fn test_star_matches_any_run() {
+        assert!(glob_match("infra*", "infra"));
+        assert!(glob_match("infra*", "infra.2"));
+        assert!(glob_match("*.prod", "api.prod"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+    }
+
+    #[test]
+    fn test_question_mark_matches_single_char() {
+        assert!(glob_match("web?", "web1"));
+        assert!(!glob_match("web?", "web"));
+        assert!(!glob_match("web?", "web12"));
+    }
+
+    #[test]
+    fn test_matches_any() {
+        let patterns = vec!["infra".to_string(), "*.keep".to_string()];
+        assert!(matches_any(&patterns, "infra"));
+        assert!(matches_any(&patterns, "notes.keep"));
+        assert!(!matches_any(&patterns, "scratch"));
+    }
+
+    #[test]
+    fn test_path_pattern_glob_and_regex() {
+        let glob = PathPattern::parse("*/node_modules*").unwrap();
+        assert!(glob.is_match("/home/u/app/node_modules/react"));
+        assert!(!glob.is_match("/home/u/app"));
+
+        let regex = PathPattern::parse("re:/(target|build)(/|$)").unwrap();
+        assert!(regex.is_match("/home/u/app/target"));
+        assert!(regex.is_match("/home/u/app/build/out"));
+        assert!(!regex.is_match("/home/u/app/targets"));
+
+        assert!(PathPattern::parse("re:(").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use super::types::SessionItem;
+
+/// Find the longest configured base path containing a path, without trailing slash
+pub fn base_path_of<'a>(path: &str, base_paths: &'a [String]) -> Option<&'a str> {
+    base_paths
+        .iter()
+        .map(|base_path| base_path.trim_end_matches('/'))
+        .filter(|base_path| {
+            // Only match on a directory boundary (not "/work" for "/workshop")
+            path.strip_prefix(base_path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .max_by_key(|base_path| base_path.len())
+}
+
+/// Split items into those without a folder and folders of items sharing a root
+/// Folders keep the order of their first item, items keep their order within a folder
+pub fn nest(
+    items: Vec<SessionItem>,
+    folder_of: impl Fn(&SessionItem) -> Option<String>,
+) -> (Vec<SessionItem>, Vec<(String, Vec<SessionItem>)>) {
+    let mut ungrouped = Vec::new();
+    let mut folders: Vec<(String, Vec<SessionItem>)> = Vec::new();
+
+    for item in items {
+        match folder_of(&item) {
+            Some(folder) => match folders.iter_mut().find(|(root, _)| *root == folder) {
+                Some((_, folder_items)) => folder_items.push(item),
+                None => folders.push((folder, vec![item])),
+            },
+            None => ungrouped.push(item),
+        }
+    }
+
+    (ungrouped, folders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(path: &str) -> SessionItem {
+        SessionItem::Directory {
+            path: path.to_string(),
+            session_name: path.to_string(),
+            vanished: false,
+        }
+    }
+
+    #[test]
+    fn test_base_path_of_prefers_longest_match() {
+        let base_paths = vec![
+            "/home/u".to_string(),
+            "/home/u/work/".to_string(),
+            "/srv".to_string(),
+        ];
+        assert_eq!(
+            base_path_of("/home/u/work/api", &base_paths),
+            Some("/home/u/work")
+        );
+        assert_eq!(base_path_of("/home/u/notes", &base_paths), Some("/home/u"));
+        assert_eq!(base_path_of("/srv", &base_paths), Some("/srv"));
+        assert_eq!(base_path_of("/srvx/app", &base_paths), None);
+        assert_eq!(base_path_of("/tmp", &base_paths), None);
+    }
+
+    #[test]
+    fn test_nest_groups_by_folder_in_first_seen_order() {
+        let items = vec![
+            directory("/p/b/1"),
+            directory("/tmp"),
+            directory("/p/a/1"),
+            directory("/p/b/2"),
+        ];
+        let (ungrouped, folders) = nest(items, |item| {
+            let path = item.name();
+            path.strip_prefix("/p/")
+                .map(|rest| format!("/p/{}", &rest[..1]))
+        });
+
+        assert_eq!(ungrouped.len(), 1);
+        assert_eq!(ungrouped[0].name(), "/tmp");
+        let roots: Vec<_> = folders
+            .iter()
+            .map(|(root, items)| (root.as_str(), items.len()))
+            .collect();
+        assert_eq!(roots, vec![("/p/b", 2), ("/p/a", 1)]);
+        assert_eq!(folders[0].1[1].name(), "/p/b/2");
+    }
+}
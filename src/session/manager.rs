@@ -1,25 +1,85 @@
+use crate::config::CleanupPolicy;
+use crate::pattern;
 use crate::session::types::SessionAction;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::time::Duration;
-use zellij_tile::prelude::{delete_dead_session, kill_sessions, switch_session, SessionInfo};
+use std::time::SystemTime;
+use zellij_tile::prelude::{
+    delete_dead_session, kill_sessions, run_command, switch_session, SessionInfo,
+};
 
 /// Number of consecutive updates a session must be missing before we remove it
 const MISSING_THRESHOLD: u8 = 3;
 
+/// Seconds a confirmed deletion can be undone before dead sessions are actually deleted
+pub const UNDO_WINDOW_SECS: u64 = 8;
+
+/// Dead sessions awaiting deletion, one `deadline_ms<TAB>name` line each, so a plugin
+/// instance unloaded during the undo window doesn't drop them (in the plugin's own /tmp folder)
+const DEFERRED_DELETIONS: &str = "/tmp/zsm-deferred-deletions";
+
+/// A confirmed deletion that can still be undone
+#[derive(Debug, Clone)]
+pub struct UndoableDeletion {
+    /// Live sessions that were killed, with their MRU timestamps
+    killed: Vec<(String, Option<u64>)>,
+    /// Resurrectable sessions whose deletion is deferred until the undo window closes
+    deferred: Vec<(String, Duration)>,
+    /// Unix timestamp in milliseconds when the undo window closes
+    deadline_ms: u64,
+}
+
+impl UndoableDeletion {
+    /// Names of all affected sessions
+    pub fn session_names(&self) -> Vec<&str> {
+        self.killed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .chain(self.deferred.iter().map(|(name, _)| name.as_str()))
+            .collect()
+    }
+
+    /// Whether any live session was killed (as opposed to only dead sessions deleted)
+    pub fn has_killed_sessions(&self) -> bool {
+        !self.killed.is_empty()
+    }
+}
+
+/// Current unix time in milliseconds
+pub fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Manages session operations and state
 #[derive(Debug, Default)]
 pub struct SessionManager {
     /// Currently known sessions from Zellij
     sessions: Vec<SessionInfo>,
-    /// Session name pending deletion confirmation
-    pending_deletion: Option<String>,
+    /// Session names pending deletion confirmation (one or more for batch operations)
+    pending_deletion: Vec<String>,
     /// Resurrectable sessions
     resurrectable_sessions: Vec<(String, Duration)>,
+    /// When the resurrectable session durations were last brought up to date (unix ms)
+    resurrectable_updated_ms: u64,
     /// Tracks how many consecutive updates each session has been missing
     /// Key is lowercase session name for case-insensitive matching
     missing_counts: HashMap<String, u8>,
     /// MRU timestamps for session ordering (session name -> unix timestamp in seconds)
     mru_timestamps: HashMap<String, u64>,
+    /// Most recent confirmed deletion, while it can still be undone
+    undoable_deletion: Option<UndoableDeletion>,
+    /// Session names or glob patterns protected from deletion (from config)
+    protected_patterns: Vec<String>,
+    /// Runtime protection toggles that override the configured patterns
+    protection_overrides: HashMap<String, bool>,
+    /// Text typed to confirm deleting a protected session
+    typed_confirmation: String,
+    /// Directories sessions were created in (session name -> directory path)
+    session_directories: HashMap<String, String>,
 }
 
 impl SessionManager {
@@ -93,6 +153,12 @@ impl SessionManager {
         &mut self,
         new_resurrectable: Vec<(String, Duration)>,
     ) -> bool {
+        // Dead sessions whose deletion is deferred for undo must stay hidden
+        let new_resurrectable: Vec<(String, Duration)> = new_resurrectable
+            .into_iter()
+            .filter(|(name, _)| !self.is_deletion_deferred(name))
+            .collect();
+
         // For resurrectable sessions, we use simpler logic:
         // Just check if the set of names changed (case-insensitive)
         let mut current_names: Vec<String> = self
@@ -111,9 +177,24 @@ impl SessionManager {
         let changed = current_names != new_names;
         // Always update to get fresh durations
         self.resurrectable_sessions = new_resurrectable;
+        self.resurrectable_updated_ms = unix_now_ms();
         changed
     }
 
+    /// Age resurrectable sessions by the time passed since their durations were last updated
+    /// Zellij only reports durations when sessions change, so they would otherwise freeze
+    pub fn age_resurrectable_sessions(&mut self) {
+        self.age_resurrectable_sessions_at(unix_now_ms());
+    }
+
+    fn age_resurrectable_sessions_at(&mut self, now_ms: u64) {
+        let elapsed = Duration::from_millis(now_ms.saturating_sub(self.resurrectable_updated_ms));
+        for (_, duration) in &mut self.resurrectable_sessions {
+            *duration += elapsed;
+        }
+        self.resurrectable_updated_ms = now_ms;
+    }
+
     /// Get all sessions
     pub fn sessions(&self) -> &[SessionInfo] {
         &self.sessions
@@ -133,9 +214,76 @@ impl SessionManager {
         }
     }
 
-    /// Start session deletion confirmation
-    pub fn start_deletion(&mut self, session_name: String) {
-        self.pending_deletion = Some(session_name);
+    /// Start deletion confirmation for one or more sessions
+    /// A single protected session requires typing its name to confirm, while protected
+    /// sessions in a batch are refused
+    /// Returns the names of refused sessions
+    pub fn start_deletion(&mut self, session_names: Vec<String>) -> Vec<String> {
+        self.typed_confirmation.clear();
+
+        if session_names.len() == 1 {
+            self.pending_deletion = session_names;
+            return Vec::new();
+        }
+
+        let (refused, allowed): (Vec<String>, Vec<String>) = session_names
+            .into_iter()
+            .partition(|name| self.is_protected(name));
+        self.pending_deletion = allowed;
+        refused
+    }
+
+    /// Set protected session names or glob patterns from config
+    pub fn set_protected_patterns(&mut self, patterns: Vec<String>) {
+        self.protected_patterns = patterns;
+    }
+
+    /// Check if a session is protected from deletion
+    pub fn is_protected(&self, session_name: &str) -> bool {
+        self.protection_overrides
+            .get(session_name)
+            .copied()
+            .unwrap_or_else(|| pattern::matches_any(&self.protected_patterns, session_name))
+    }
+
+    /// Toggle protection of a session at runtime
+    /// Returns true if the session is now protected
+    pub fn toggle_protected(&mut self, session_name: &str) -> bool {
+        let protected = !self.is_protected(session_name);
+        self.protection_overrides
+            .insert(session_name.to_string(), protected);
+        protected
+    }
+
+    /// Get the protected session whose name must be typed to confirm deletion
+    pub fn typed_confirmation_target(&self) -> Option<&str> {
+        match self.pending_deletion.as_slice() {
+            [session_name] if self.is_protected(session_name) => Some(session_name),
+            _ => None,
+        }
+    }
+
+    /// Get the text typed so far to confirm deleting a protected session
+    pub fn typed_confirmation(&self) -> &str {
+        &self.typed_confirmation
+    }
+
+    /// Add a character to the typed confirmation
+    pub fn push_typed_confirmation(&mut self, c: char) {
+        self.typed_confirmation.push(c);
+    }
+
+    /// Remove the last character from the typed confirmation
+    pub fn pop_typed_confirmation(&mut self) {
+        self.typed_confirmation.pop();
+    }
+
+    /// Check if deletion may be confirmed (typed name matches for protected sessions)
+    pub fn can_confirm_deletion(&self) -> bool {
+        match self.typed_confirmation_target() {
+            Some(session_name) => self.typed_confirmation == session_name,
+            None => !self.pending_deletion.is_empty(),
+        }
     }
 
     /// Rename a session in local state (optimistic update)
@@ -153,11 +301,30 @@ impl SessionManager {
             session.name = new_name.to_string();
         }
 
+        // Update in resurrectable sessions
+        if let Some((name, _)) = self
+            .resurrectable_sessions
+            .iter_mut()
+            .find(|(name, _)| name.to_lowercase() == old_key)
+        {
+            *name = new_name.to_string();
+        }
+
         // Update MRU timestamps (transfer old name's timestamp to new name)
         if let Some(timestamp) = self.mru_timestamps.remove(old_name) {
             self.mru_timestamps.insert(new_name.to_string(), timestamp);
         }
 
+        // Carry directory association and protection toggle along
+        if let Some(directory) = self.session_directories.remove(old_name) {
+            self.session_directories
+                .insert(new_name.to_string(), directory);
+        }
+        if let Some(protected) = self.protection_overrides.remove(old_name) {
+            self.protection_overrides
+                .insert(new_name.to_string(), protected);
+        }
+
         // Clean up missing counts for old name
         self.missing_counts.remove(&old_key);
         // Pre-emptively remove new name from missing counts in case it was tracked
@@ -180,39 +347,240 @@ impl SessionManager {
         self.missing_counts.remove(&key);
     }
 
-    /// Confirm session deletion
-    /// Uses optimistic update - removes from local state immediately before sending kill command
+    /// Split session names into (resurrectable, live) based on local state
+    /// Resurrectable sessions must be deleted, live sessions must be killed
+    fn split_by_resurrectable(&self, session_names: &[String]) -> (Vec<String>, Vec<String>) {
+        session_names.iter().cloned().partition(|session_name| {
+            self.resurrectable_sessions
+                .iter()
+                .any(|(name, _)| name == session_name)
+        })
+    }
+
+    /// Confirm deletion of all pending sessions
+    /// Uses optimistic update - removes from local state immediately before sending kill commands
+    /// Live sessions are killed right away, dead sessions are only deleted once the undo window closes
     pub fn confirm_deletion(&mut self) {
-        if let Some(session_name) = self.pending_deletion.take() {
-            // Check if it's a resurrectable session BEFORE the optimistic removal
-            // (removal clears the list, so we must check first)
-            let is_resurrectable = self
-                .resurrectable_sessions
+        if !self.can_confirm_deletion() {
+            return;
+        }
+        let session_names = std::mem::take(&mut self.pending_deletion);
+        self.typed_confirmation.clear();
+
+        // Only the most recent deletion can be undone - finalize the previous one
+        self.finalize_undoable_deletion();
+
+        let live =
+            self.begin_undoable_deletion(session_names, unix_now_ms() + UNDO_WINDOW_SECS * 1000);
+
+        if let Some(deletion) = &self.undoable_deletion {
+            persist_deferred_deletions(|lines| lines.extend(deferred_deletion_lines(deletion)));
+        }
+
+        // Now execute the actual kill action
+        // If this fails, the session will reappear on the next SessionUpdate event
+        if !live.is_empty() {
+            kill_sessions(&live);
+        }
+    }
+
+    /// Record an undoable deletion and remove its sessions from local state
+    /// Returns the live sessions that must be killed
+    fn begin_undoable_deletion(
+        &mut self,
+        session_names: Vec<String>,
+        deadline_ms: u64,
+    ) -> Vec<String> {
+        // Check which sessions are resurrectable BEFORE the optimistic removal
+        // (removal clears the list, so we must check first)
+        let (resurrectable, live) = self.split_by_resurrectable(&session_names);
+
+        let deferred = self
+            .resurrectable_sessions
+            .iter()
+            .filter(|(name, _)| resurrectable.contains(name))
+            .cloned()
+            .collect();
+        let killed = live
+            .iter()
+            .map(|name| (name.clone(), self.mru_timestamps.get(name).copied()))
+            .collect();
+
+        // Optimistic update: Remove sessions from local state immediately
+        // This provides instant UI feedback rather than waiting for MISSING_THRESHOLD updates
+        for session_name in &session_names {
+            self.remove_session_from_local_state(session_name);
+        }
+
+        self.undoable_deletion = Some(UndoableDeletion {
+            killed,
+            deferred,
+            deadline_ms,
+        });
+        live
+    }
+
+    /// Get the deletion that can currently be undone
+    pub fn undoable_deletion(&self) -> Option<&UndoableDeletion> {
+        self.undoable_deletion.as_ref()
+    }
+
+    /// Check if a dead session's deletion is deferred for undo
+    fn is_deletion_deferred(&self, session_name: &str) -> bool {
+        self.undoable_deletion.as_ref().is_some_and(|deletion| {
+            deletion
+                .deferred
                 .iter()
-                .any(|(name, _)| name == &session_name);
+                .any(|(name, _)| name == session_name)
+        })
+    }
 
-            // Optimistic update: Remove session from local state immediately
-            // This provides instant UI feedback rather than waiting for MISSING_THRESHOLD updates
-            self.remove_session_from_local_state(&session_name);
+    /// Take the undoable deletion if its undo window has closed
+    fn take_expired_deletion(&mut self, now_ms: u64) -> Option<UndoableDeletion> {
+        // Timers may fire slightly early, allow a small tolerance
+        if self
+            .undoable_deletion
+            .as_ref()
+            .is_some_and(|deletion| now_ms + 250 >= deletion.deadline_ms)
+        {
+            self.undoable_deletion.take()
+        } else {
+            None
+        }
+    }
 
-            // Now execute the actual kill/delete action
-            // If this fails, the session will reappear on the next SessionUpdate event
-            if is_resurrectable {
-                delete_dead_session(&session_name);
-            } else {
-                kill_sessions(&[&session_name]);
+    /// Finalize the undoable deletion if its undo window has closed
+    /// Returns true if the deletion was finalized (the undo notice should disappear)
+    pub fn expire_undoable_deletion(&mut self) -> bool {
+        match self.take_expired_deletion(unix_now_ms()) {
+            Some(deletion) => {
+                delete_deferred_sessions(&deletion);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finalize the undoable deletion immediately, regardless of its undo window
+    fn finalize_undoable_deletion(&mut self) {
+        if let Some(deletion) = self.undoable_deletion.take() {
+            delete_deferred_sessions(&deletion);
+        }
+    }
+
+    /// Delete dead sessions left deferred by plugin instances unloaded during their undo window
+    pub fn finalize_abandoned_deletions(&mut self) {
+        let now_ms = unix_now_ms();
+        persist_deferred_deletions(|lines| {
+            let (due, pending) = split_due_deletions(lines, now_ms);
+            for name in &due {
+                delete_dead_session(name);
+            }
+            *lines = pending;
+        });
+    }
+
+    /// Restore local state for the undoable deletion
+    fn restore_undoable_deletion(&mut self) -> Option<UndoableDeletion> {
+        let deletion = self.undoable_deletion.take()?;
+
+        for (name, duration) in &deletion.deferred {
+            if !self.resurrectable_sessions.iter().any(|(n, _)| n == name) {
+                self.resurrectable_sessions.push((name.clone(), *duration));
+            }
+        }
+        for (name, timestamp) in &deletion.killed {
+            if let Some(timestamp) = timestamp {
+                self.mru_timestamps.insert(name.clone(), *timestamp);
             }
         }
+
+        Some(deletion)
+    }
+
+    /// Killed sessions of a deletion that Zellij does not list as resurrectable (yet),
+    /// so attaching to them would create a new empty session instead of restoring them
+    fn unrestorable_sessions(&self, deletion: &UndoableDeletion) -> Vec<String> {
+        deletion
+            .killed
+            .iter()
+            .map(|(name, _)| name)
+            .filter(|name| !self.resurrectable_sessions.iter().any(|(n, _)| n == *name))
+            .cloned()
+            .collect()
+    }
+
+    /// Undo the most recent deletion
+    /// Dead sessions are simply kept, killed sessions are resurrected in the background
+    /// Returns None if there was nothing to undo, otherwise the killed sessions that
+    /// could not be brought back
+    pub fn undo_deletion(&mut self) -> Option<Vec<String>> {
+        let deletion = self.restore_undoable_deletion()?;
+        forget_deferred_deletions(&deletion);
+
+        let unrestorable = self.unrestorable_sessions(&deletion);
+        for (session_name, _) in &deletion.killed {
+            if unrestorable.contains(session_name) {
+                continue;
+            }
+            // A killed session becomes resurrectable, so attaching in the background brings it back
+            let mut context = BTreeMap::new();
+            context.insert("zsm_internal".to_string(), "resurrect".to_string());
+            run_command(
+                &["zellij", "attach", "--create-background", session_name],
+                context,
+            );
+        }
+        Some(unrestorable)
+    }
+
+    /// Find resurrectable sessions that exceed the cleanup policy
+    /// Excluded and protected sessions are never returned and do not count towards `max_count`
+    pub fn cleanup_candidates(&self, policy: &CleanupPolicy) -> Vec<String> {
+        if !policy.is_enabled() {
+            return Vec::new();
+        }
+
+        // Most recent (smallest duration) first so max_count keeps the newest sessions
+        let mut eligible: Vec<&(String, Duration)> = self
+            .resurrectable_sessions
+            .iter()
+            .filter(|(name, _)| {
+                !pattern::matches_any(&policy.exclude, name) && !self.is_protected(name)
+            })
+            .collect();
+        eligible.sort_by_key(|(_, duration)| *duration);
+
+        eligible
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, duration))| {
+                let too_old = policy.max_age.is_some_and(|max_age| *duration > max_age);
+                let too_many = policy.max_count.is_some_and(|max_count| *i >= max_count);
+                too_old || too_many
+            })
+            .map(|(_, (name, _))| name.clone())
+            .collect()
+    }
+
+    /// Delete resurrectable sessions without confirmation or undo (used by the cleanup policy)
+    /// Uses optimistic update like confirm_deletion
+    pub fn delete_dead_sessions(&mut self, session_names: &[String]) {
+        for session_name in session_names {
+            self.remove_session_from_local_state(session_name);
+            delete_dead_session(session_name);
+        }
     }
 
     /// Cancel session deletion
     pub fn cancel_deletion(&mut self) {
-        self.pending_deletion = None;
+        self.pending_deletion.clear();
+        self.typed_confirmation.clear();
     }
 
-    /// Get session pending deletion
-    pub fn pending_deletion(&self) -> Option<&str> {
-        self.pending_deletion.as_deref()
+    /// Get sessions pending deletion (empty if no confirmation is pending)
+    pub fn pending_deletion(&self) -> &[String] {
+        &self.pending_deletion
     }
 
     /// Record a session switch for MRU tracking
@@ -241,6 +609,38 @@ impl SessionManager {
         &self.mru_timestamps
     }
 
+    /// Record the directory a session belongs to
+    pub fn set_session_directory(&mut self, session_name: &str, directory: &str) {
+        self.session_directories
+            .insert(session_name.to_string(), directory.to_string());
+    }
+
+    /// Set session directory associations from persisted data
+    pub fn set_session_directories(&mut self, directories: HashMap<String, String>) {
+        self.session_directories = directories;
+    }
+
+    /// Get the recorded directory of a session
+    pub fn session_directory(&self, session_name: &str) -> Option<&str> {
+        self.session_directories
+            .get(session_name)
+            .map(|directory| directory.as_str())
+    }
+
+    /// Get all recorded session directories by session name
+    pub fn session_directories(&self) -> &HashMap<String, String> {
+        &self.session_directories
+    }
+
+    /// Check if a name is already used by a live or resurrectable session
+    pub fn session_name_exists(&self, session_name: &str) -> bool {
+        self.sessions.iter().any(|s| s.name == session_name)
+            || self
+                .resurrectable_sessions
+                .iter()
+                .any(|(name, _)| name == session_name)
+    }
+
     /// Get MRU rank for a session (higher = more recent)
     /// Returns 0 if session has no recorded timestamp
     pub fn get_mru_rank(&self, session_name: &str) -> u64 {
@@ -279,6 +679,64 @@ impl SessionManager {
     }
 }
 
+/// Delete the deferred dead sessions of a deletion that can no longer be undone
+fn delete_deferred_sessions(deletion: &UndoableDeletion) {
+    for (session_name, _) in &deletion.deferred {
+        delete_dead_session(session_name);
+    }
+    forget_deferred_deletions(deletion);
+}
+
+/// Lines of `DEFERRED_DELETIONS` recording the deferred dead sessions of a deletion
+fn deferred_deletion_lines(deletion: &UndoableDeletion) -> Vec<String> {
+    deletion
+        .deferred
+        .iter()
+        .map(|(name, _)| format!("{}\t{}", deletion.deadline_ms, name))
+        .collect()
+}
+
+/// Remove a finalized or undone deletion from `DEFERRED_DELETIONS`
+fn forget_deferred_deletions(deletion: &UndoableDeletion) {
+    if deletion.deferred.is_empty() {
+        return;
+    }
+    let own_lines = deferred_deletion_lines(deletion);
+    persist_deferred_deletions(|lines| lines.retain(|line| !own_lines.contains(line)));
+}
+
+/// Update the lines of `DEFERRED_DELETIONS`
+/// Best effort: without the file, deletions of unloaded instances are dropped as before
+fn persist_deferred_deletions(update: impl FnOnce(&mut Vec<String>)) {
+    let mut lines: Vec<String> = std::fs::read_to_string(DEFERRED_DELETIONS)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    update(&mut lines);
+    let data: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    let _ = std::fs::write(DEFERRED_DELETIONS, data);
+}
+
+/// Split `DEFERRED_DELETIONS` lines into the sessions whose undo window has closed
+/// and the lines still within it (malformed lines are dropped)
+fn split_due_deletions(lines: &[String], now_ms: u64) -> (Vec<String>, Vec<String>) {
+    let mut due = Vec::new();
+    let mut pending = Vec::new();
+    for line in lines {
+        let Some((deadline, name)) = line.split_once('\t') else {
+            continue;
+        };
+        match deadline.parse::<u64>() {
+            Ok(deadline_ms) if deadline_ms <= now_ms => due.push(name.to_string()),
+            Ok(_) => pending.push(line.clone()),
+            Err(_) => {}
+        }
+    }
+    (due, pending)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -512,4 +970,292 @@ mod tests {
         assert_eq!(manager.get_mru_rank("new-name"), old_ts);
         assert_eq!(manager.get_mru_rank("old-name"), 0);
     }
+
+    #[test]
+    fn test_start_deletion_holds_whole_batch() {
+        let mut manager = SessionManager::default();
+
+        manager.start_deletion(vec!["one".to_string(), "two".to_string()]);
+
+        assert_eq!(manager.pending_deletion(), ["one", "two"]);
+
+        manager.cancel_deletion();
+        assert!(manager.pending_deletion().is_empty());
+    }
+
+    #[test]
+    fn test_split_by_resurrectable_separates_live_and_dead() {
+        let mut manager = SessionManager::default();
+
+        manager.update_sessions_stable(vec![make_session("live", false)]);
+        manager.update_resurrectable_stable(vec![("dead".to_string(), Duration::from_secs(60))]);
+
+        let (resurrectable, live) =
+            manager.split_by_resurrectable(&["live".to_string(), "dead".to_string()]);
+
+        assert_eq!(resurrectable, vec!["dead".to_string()]);
+        assert_eq!(live, vec!["live".to_string()]);
+    }
+
+    fn make_dead(names_and_ages: &[(&str, u64)]) -> Vec<(String, Duration)> {
+        names_and_ages
+            .iter()
+            .map(|(name, secs)| (name.to_string(), Duration::from_secs(*secs)))
+            .collect()
+    }
+
+    #[test]
+    fn test_cleanup_disabled_without_limits() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("old", 1_000_000)]));
+
+        let candidates = manager.cleanup_candidates(&CleanupPolicy::default());
+
+        assert!(candidates.is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_max_age_selects_old_sessions() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("new", 60), ("old", 7200)]));
+
+        let policy = CleanupPolicy {
+            max_age: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+
+        assert_eq!(manager.cleanup_candidates(&policy), vec!["old".to_string()]);
+    }
+
+    #[test]
+    fn test_cleanup_max_count_keeps_most_recent() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("c", 300), ("a", 100), ("b", 200)]));
+
+        let policy = CleanupPolicy {
+            max_count: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(manager.cleanup_candidates(&policy), vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_cleanup_excluded_sessions_are_kept_and_not_counted() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[
+            ("infra", 10),
+            ("a", 100),
+            ("b", 200),
+            ("infra.old", 99_999),
+        ]));
+
+        let policy = CleanupPolicy {
+            max_age: Some(Duration::from_secs(10_000)),
+            max_count: Some(1),
+            exclude: vec!["infra*".to_string()],
+            dry_run: false,
+        };
+
+        assert_eq!(manager.cleanup_candidates(&policy), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_undoable_deletion_defers_dead_sessions() {
+        let mut manager = SessionManager::default();
+        manager.update_sessions_stable(vec![make_session("live", false)]);
+        manager.update_resurrectable_stable(make_dead(&[("dead", 60)]));
+
+        let live =
+            manager.begin_undoable_deletion(vec!["live".to_string(), "dead".to_string()], 1000);
+
+        // Only the live session is killed right away
+        assert_eq!(live, vec!["live".to_string()]);
+        assert!(manager.sessions().is_empty());
+        assert!(manager.resurrectable_sessions().is_empty());
+
+        // The deferred dead session stays hidden even if Zellij still reports it
+        manager.update_resurrectable_stable(make_dead(&[("dead", 60), ("live", 0)]));
+        assert_eq!(manager.resurrectable_sessions().len(), 1);
+        assert_eq!(manager.resurrectable_sessions()[0].0, "live");
+    }
+
+    #[test]
+    fn test_restore_undoable_deletion_restores_dead_session_and_mru() {
+        let mut manager = SessionManager::default();
+        manager.update_sessions_stable(vec![make_session("live", false)]);
+        manager.update_resurrectable_stable(make_dead(&[("dead", 60)]));
+        manager.record_switch("live");
+        let live_ts = manager.get_mru_rank("live");
+
+        manager.begin_undoable_deletion(vec!["live".to_string(), "dead".to_string()], 1000);
+        manager.mru_timestamps.remove("live");
+
+        let restored = manager.restore_undoable_deletion();
+
+        assert!(restored.is_some());
+        assert!(manager.undoable_deletion().is_none());
+        assert_eq!(manager.resurrectable_sessions()[0].0, "dead");
+        assert_eq!(manager.get_mru_rank("live"), live_ts);
+    }
+
+    #[test]
+    fn test_undo_only_restores_killed_sessions_saved_as_resurrectable() {
+        let mut manager = SessionManager::default();
+        manager.update_sessions_stable(vec![make_session("a", false), make_session("b", false)]);
+        manager.begin_undoable_deletion(vec!["a".to_string(), "b".to_string()], 1000);
+
+        // Zellij has only serialized "a" so far
+        manager.update_resurrectable_stable(make_dead(&[("a", 0)]));
+        let deletion = manager.restore_undoable_deletion().unwrap();
+        assert_eq!(
+            manager.unrestorable_sessions(&deletion),
+            vec!["b".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_deferred_deletions_are_due_after_deadline() {
+        let lines = vec![
+            "1000\told".to_string(),
+            "9000\tnew".to_string(),
+            "garbage".to_string(),
+        ];
+        let (due, pending) = split_due_deletions(&lines, 5000);
+        assert_eq!(due, vec!["old".to_string()]);
+        assert_eq!(pending, vec!["9000\tnew".to_string()]);
+    }
+
+    #[test]
+    fn test_deferred_deletion_is_case_sensitive() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("foo", 60)]));
+        manager.begin_undoable_deletion(vec!["foo".to_string()], 1000);
+
+        manager.update_resurrectable_stable(make_dead(&[("foo", 60), ("Foo", 30)]));
+        assert_eq!(manager.resurrectable_sessions().len(), 1);
+        assert_eq!(manager.resurrectable_sessions()[0].0, "Foo");
+    }
+
+    #[test]
+    fn test_age_resurrectable_sessions() {
+        let mut manager = SessionManager {
+            resurrectable_sessions: vec![("old".to_string(), Duration::from_secs(60))],
+            resurrectable_updated_ms: 10_000,
+            ..Default::default()
+        };
+
+        manager.age_resurrectable_sessions_at(40_000);
+        manager.age_resurrectable_sessions_at(45_000);
+        assert_eq!(
+            manager.resurrectable_sessions(),
+            &[("old".to_string(), Duration::from_secs(95))]
+        );
+    }
+
+    #[test]
+    fn test_undoable_deletion_expires_after_deadline() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("dead", 60)]));
+        manager.begin_undoable_deletion(vec!["dead".to_string()], 10_000);
+
+        assert!(manager.take_expired_deletion(5_000).is_none());
+        assert!(manager.undoable_deletion().is_some());
+
+        assert!(manager.take_expired_deletion(10_000).is_some());
+        assert!(manager.undoable_deletion().is_none());
+    }
+
+    #[test]
+    fn test_protected_patterns_and_runtime_toggle() {
+        let mut manager = SessionManager::default();
+        manager.set_protected_patterns(vec!["infra*".to_string()]);
+
+        assert!(manager.is_protected("infra"));
+        assert!(manager.is_protected("infra.2"));
+        assert!(!manager.is_protected("scratch"));
+
+        // Runtime toggle overrides config in both directions
+        assert!(!manager.toggle_protected("infra"));
+        assert!(!manager.is_protected("infra"));
+        assert!(manager.toggle_protected("scratch"));
+        assert!(manager.is_protected("scratch"));
+    }
+
+    #[test]
+    fn test_single_protected_deletion_requires_typed_name() {
+        let mut manager = SessionManager::default();
+        manager.set_protected_patterns(vec!["infra".to_string()]);
+
+        let refused = manager.start_deletion(vec!["infra".to_string()]);
+
+        assert!(refused.is_empty());
+        assert_eq!(manager.typed_confirmation_target(), Some("infra"));
+        assert!(!manager.can_confirm_deletion());
+
+        for c in "infra".chars() {
+            manager.push_typed_confirmation(c);
+        }
+        assert!(manager.can_confirm_deletion());
+    }
+
+    #[test]
+    fn test_batch_deletion_refuses_protected_sessions() {
+        let mut manager = SessionManager::default();
+        manager.set_protected_patterns(vec!["infra".to_string()]);
+
+        let refused = manager.start_deletion(vec!["infra".to_string(), "scratch".to_string()]);
+
+        assert_eq!(refused, vec!["infra".to_string()]);
+        assert_eq!(manager.pending_deletion(), ["scratch"]);
+        assert!(manager.typed_confirmation_target().is_none());
+        assert!(manager.can_confirm_deletion());
+    }
+
+    #[test]
+    fn test_cleanup_skips_protected_sessions() {
+        let mut manager = SessionManager::default();
+        manager.set_protected_patterns(vec!["keep".to_string()]);
+        manager.update_resurrectable_stable(make_dead(&[("keep", 99_999), ("drop", 99_999)]));
+
+        let policy = CleanupPolicy {
+            max_age: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            manager.cleanup_candidates(&policy),
+            vec!["drop".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_optimistic_rename_updates_resurrectable_session() {
+        let mut manager = SessionManager::default();
+        manager.update_resurrectable_stable(make_dead(&[("old-name", 60)]));
+
+        manager.rename_session_in_local_state("old-name", "new-name");
+
+        assert_eq!(manager.resurrectable_sessions()[0].0, "new-name");
+        assert!(manager.session_name_exists("new-name"));
+        assert!(!manager.session_name_exists("old-name"));
+    }
+
+    #[test]
+    fn test_optimistic_rename_transfers_directory_and_protection() {
+        let mut manager = SessionManager::default();
+        manager.update_sessions_stable(vec![make_session("old-name", false)]);
+        manager.set_session_directory("old-name", "/home/user/project");
+        manager.toggle_protected("old-name");
+
+        manager.rename_session_in_local_state("old-name", "new-name");
+
+        assert_eq!(
+            manager.session_directory("new-name"),
+            Some("/home/user/project")
+        );
+        assert_eq!(manager.session_directory("old-name"), None);
+        assert!(manager.is_protected("new-name"));
+        assert!(!manager.is_protected("old-name"));
+    }
 }
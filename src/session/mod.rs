@@ -1,5 +1,8 @@
+pub mod grouping;
 pub mod manager;
+pub mod selection;
 pub mod types;
 
-pub use manager::SessionManager;
-pub use types::{SessionAction, SessionItem};
+pub use manager::{unix_now_ms, SessionManager, UNDO_WINDOW_SECS};
+pub use selection::Selection;
+pub use types::{ItemGroup, ItemKey, ListEntry, SessionAction, SessionItem};
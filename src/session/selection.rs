@@ -0,0 +1,189 @@
+use super::types::{ItemKey, ListEntry};
+
+/// Selected list item, tracked by identity so the highlight stays on the same item
+/// when the list is rebuilt
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Identity of the selected item
+    key: Option<ItemKey>,
+    /// Position of the selected item when last resolved, used to pick a neighbour
+    /// when the item disappears
+    index: usize,
+}
+
+impl Selection {
+    /// Check whether nothing is selected
+    pub fn is_none(&self) -> bool {
+        self.key.is_none()
+    }
+
+    /// Clear the selection
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.index = 0;
+    }
+
+    /// Select the entry at an index, clamped to the list
+    pub fn select<T: ListEntry>(&mut self, entries: &[T], index: usize) -> Option<usize> {
+        if entries.is_empty() {
+            self.clear();
+            return None;
+        }
+        let index = index.min(entries.len() - 1);
+        self.key = Some(entries[index].session_item().key());
+        self.index = index;
+        Some(index)
+    }
+
+    /// Find the selected entry in a list without updating the selection
+    /// Falls back to the entry now at the old position (nearest neighbour) if it disappeared
+    pub fn find<T: ListEntry>(&self, entries: &[T]) -> Option<usize> {
+        let key = self.key.as_ref()?;
+        if entries.is_empty() {
+            return None;
+        }
+        entries
+            .iter()
+            .position(|entry| entry.session_item().has_key(key))
+            .or(Some(self.index.min(entries.len() - 1)))
+    }
+
+    /// Re-resolve the selection against a rebuilt list
+    /// Moves to the nearest neighbour if the selected entry disappeared
+    pub fn resolve<T: ListEntry>(&mut self, entries: &[T]) -> Option<usize> {
+        match self.find(entries) {
+            Some(index) => self.select(entries, index),
+            None => {
+                if entries.is_empty() {
+                    // Keep the identity so the selection comes back if the entry reappears
+                    self.index = 0;
+                }
+                None
+            }
+        }
+    }
+
+    /// Move the selection up, wrapping to the last entry
+    pub fn move_up<T: ListEntry>(&mut self, entries: &[T]) {
+        let index = match self.find(entries) {
+            Some(0) | None => entries.len().saturating_sub(1),
+            Some(index) => index - 1,
+        };
+        self.select(entries, index);
+    }
+
+    /// Move the selection down, wrapping to the first entry
+    pub fn move_down<T: ListEntry>(&mut self, entries: &[T]) {
+        let index = match self.find(entries) {
+            Some(index) if index + 1 < entries.len() => index + 1,
+            _ => 0,
+        };
+        self.select(entries, index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionItem;
+
+    fn session(name: &str) -> SessionItem {
+        SessionItem::ExistingSession {
+            name: name.to_string(),
+            directory: String::new(),
+            is_current: false,
+        }
+    }
+
+    fn directory(path: &str) -> SessionItem {
+        SessionItem::Directory {
+            path: path.to_string(),
+            session_name: path.to_string(),
+            vanished: false,
+        }
+    }
+
+    #[test]
+    fn test_selection_follows_item_when_list_shifts() {
+        let mut selection = Selection::default();
+        let items = vec![session("a"), session("b"), directory("/c")];
+        selection.select(&items, 1);
+
+        // A new session sorted in front of the selected one
+        let items = vec![session("new"), session("a"), session("b"), directory("/c")];
+        assert_eq!(selection.resolve(&items), Some(2));
+    }
+
+    #[test]
+    fn test_selection_falls_back_to_neighbour() {
+        let mut selection = Selection::default();
+        let items = vec![session("a"), session("b"), session("c")];
+        selection.select(&items, 1);
+
+        // Selected item removed: the item that took its place is selected
+        let items = vec![session("a"), session("c")];
+        assert_eq!(selection.resolve(&items), Some(1));
+        assert!(items[1].has_key(&ItemKey::Session("c".to_string())));
+
+        // Last item removed: the previous item is selected
+        let items = vec![session("a")];
+        assert_eq!(selection.resolve(&items), Some(0));
+    }
+
+    #[test]
+    fn test_same_name_different_kind_is_a_different_item() {
+        let mut selection = Selection::default();
+        let items = vec![session("x"), directory("x")];
+        selection.select(&items, 1);
+
+        let items = vec![directory("x"), session("x")];
+        assert_eq!(selection.resolve(&items), Some(0));
+    }
+
+    #[test]
+    fn test_empty_list_keeps_identity() {
+        let mut selection = Selection::default();
+        let items = vec![session("a"), session("b")];
+        selection.select(&items, 1);
+
+        let empty: Vec<SessionItem> = Vec::new();
+        assert_eq!(selection.resolve(&empty), None);
+        assert_eq!(selection.resolve(&items), Some(1));
+    }
+
+    #[test]
+    fn test_move_wraps_around() {
+        let mut selection = Selection::default();
+        let items = vec![session("a"), session("b")];
+
+        selection.move_up(&items);
+        assert_eq!(selection.find(&items), Some(1));
+        selection.move_down(&items);
+        assert_eq!(selection.find(&items), Some(0));
+        selection.move_up(&items);
+        assert_eq!(selection.find(&items), Some(1));
+    }
+
+    #[test]
+    fn test_header_keeps_selection_when_section_collapses() {
+        use crate::session::ItemGroup;
+        let header = |collapsed, count| SessionItem::Header {
+            group: ItemGroup::Directories,
+            folder: None,
+            count,
+            collapsed,
+        };
+        let mut selection = Selection::default();
+        let items = vec![
+            session("a"),
+            header(false, 2),
+            directory("/b"),
+            directory("/c"),
+        ];
+        selection.select(&items, 1);
+
+        // Collapsing changes the header's count display but not its identity
+        let items = vec![session("a"), header(true, 2)];
+        assert_eq!(selection.resolve(&items), Some(1));
+    }
+}
@@ -13,7 +13,77 @@ pub enum SessionItem {
         duration: std::time::Duration,
     },
     /// A zoxide directory that can be used to create a new session
-    Directory { path: String, session_name: String },
+    Directory {
+        path: String,
+        session_name: String,
+        /// The directory no longer exists on disk
+        vanished: bool,
+    },
+    /// Header row of a list section, or of a folder (base path or project root)
+    /// nested in a section when grouping is enabled
+    Header {
+        group: ItemGroup,
+        folder: Option<String>,
+        count: usize,
+        collapsed: bool,
+    },
+}
+
+/// Section of the main list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemGroup {
+    /// Live sessions
+    Sessions,
+    /// Resurrectable sessions
+    Resurrectable,
+    /// Zoxide directories
+    Directories,
+    /// Directories found by the filesystem search (search results only)
+    FoundOnDisk,
+}
+
+impl ItemGroup {
+    /// All sections of the main list, in list order
+    pub const ALL: [ItemGroup; 3] = [
+        ItemGroup::Sessions,
+        ItemGroup::Resurrectable,
+        ItemGroup::Directories,
+    ];
+
+    /// Title shown in the section header
+    pub fn label(&self) -> &'static str {
+        match self {
+            ItemGroup::Sessions => "Sessions",
+            ItemGroup::Resurrectable => "Resurrectable",
+            ItemGroup::Directories => "Directories",
+            ItemGroup::FoundOnDisk => "Found on disk",
+        }
+    }
+
+    /// Identifier used when persisting collapse state
+    pub fn id(&self) -> &'static str {
+        match self {
+            ItemGroup::Sessions => "sessions",
+            ItemGroup::Resurrectable => "resurrectable",
+            ItemGroup::Directories => "directories",
+            ItemGroup::FoundOnDisk => "found_on_disk",
+        }
+    }
+
+    /// Look up a group by its persisted identifier
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|group| group.id() == id)
+    }
+
+    /// Get the section an item belongs to
+    pub fn of(item: &SessionItem) -> Self {
+        match item {
+            SessionItem::ExistingSession { .. } => ItemGroup::Sessions,
+            SessionItem::ResurrectableSession { .. } => ItemGroup::Resurrectable,
+            SessionItem::Directory { .. } => ItemGroup::Directories,
+            SessionItem::Header { group, .. } => *group,
+        }
+    }
 }
 
 impl SessionItem {
@@ -31,13 +101,81 @@ impl SessionItem {
             SessionItem::ExistingSession { name, .. } => name,
             SessionItem::ResurrectableSession { name, .. } => name,
             SessionItem::Directory { session_name, .. } => session_name,
+            SessionItem::Header { group, folder, .. } => folder.as_deref().unwrap_or(group.label()),
+        }
+    }
+}
+
+/// Identity of a list item (kind + name or path), stable across list rebuilds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemKey {
+    /// A live session by name
+    Session(String),
+    /// A resurrectable session by name
+    Resurrectable(String),
+    /// A zoxide directory by path
+    Directory(String),
+    /// A section or folder header
+    Header(ItemGroup, Option<String>),
+}
+
+impl SessionItem {
+    /// Get the identity of this item
+    pub fn key(&self) -> ItemKey {
+        match self {
+            SessionItem::ExistingSession { name, .. } => ItemKey::Session(name.clone()),
+            SessionItem::ResurrectableSession { name, .. } => ItemKey::Resurrectable(name.clone()),
+            SessionItem::Directory { path, .. } => ItemKey::Directory(path.clone()),
+            SessionItem::Header { group, folder, .. } => ItemKey::Header(*group, folder.clone()),
+        }
+    }
+
+    /// Check whether this item has the given identity
+    pub fn has_key(&self, key: &ItemKey) -> bool {
+        match (self, key) {
+            (SessionItem::ExistingSession { name, .. }, ItemKey::Session(key_name)) => {
+                name == key_name
+            }
+            (SessionItem::ResurrectableSession { name, .. }, ItemKey::Resurrectable(key_name)) => {
+                name == key_name
+            }
+            (SessionItem::Directory { path, .. }, ItemKey::Directory(key_path)) => path == key_path,
+            (SessionItem::Header { group, folder, .. }, ItemKey::Header(key_group, key_folder)) => {
+                group == key_group && folder == key_folder
+            }
+            _ => false,
         }
     }
 }
 
+/// A list entry wrapping a session item (plain items or search results)
+pub trait ListEntry {
+    /// Get the wrapped session item
+    fn session_item(&self) -> &SessionItem;
+}
+
+impl ListEntry for SessionItem {
+    fn session_item(&self) -> &SessionItem {
+        self
+    }
+}
+
 /// Actions that can be performed on sessions
 #[derive(Debug, Clone)]
 pub enum SessionAction {
     /// Switch to an existing session
     Switch(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_group_id_round_trip() {
+        for group in ItemGroup::ALL {
+            assert_eq!(ItemGroup::from_id(group.id()), Some(group));
+        }
+        assert_eq!(ItemGroup::from_id("unknown"), None);
+    }
+}
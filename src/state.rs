@@ -1,11 +1,21 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::time::Duration;
 use zellij_tile::prelude::*;
 
-use crate::config::{Config, SortOrder};
+use crate::browser::DirectoryBrowser;
+use crate::config::{Config, FolderPicker, GroupBy, SortOrder};
+use crate::keybindings::{Action, Keymap};
 use crate::new_session_info::NewSessionInfo;
-use crate::session::{SessionAction, SessionItem, SessionManager};
-use crate::zoxide::{SearchEngine, ZoxideDirectory};
+use crate::path_entry::{self, PathEntry};
+use crate::session::grouping;
+use crate::session::{
+    unix_now_ms, ItemGroup, ItemKey, Selection, SessionAction, SessionItem, SessionManager,
+    UNDO_WINDOW_SECS,
+};
+use crate::tab_layout;
+use crate::ui::{ListGeometry, TextInput};
+use crate::zoxide::parser::ParseResult;
+use crate::zoxide::{self, DirectorySource, SearchEngine, SourceDiagnostics, ZoxideDirectory};
 
 /// The main plugin state
 #[derive(Default)]
@@ -16,10 +26,26 @@ pub struct PluginState {
     session_manager: SessionManager,
     /// Zoxide directories (managed separately from sessions)
     zoxide_directories: Vec<ZoxideDirectory>,
+    /// Every directory source has answered since startup (until then, cached directories are shown)
+    directories_loaded: bool,
+    /// Directory sources that have answered since startup, by source index
+    answered_sources: BTreeSet<usize>,
+    /// Directory browser for the folder of a new session
+    browser: DirectoryBrowser,
+    /// Home directory of the host user, once read
+    home_dir: Option<std::path::PathBuf>,
+    /// Search term waiting to be searched on disk, and when (unix ms)
+    pending_disk_search: Option<(String, u64)>,
+    /// When the next periodic refresh is due (unix ms), if `refresh_interval` is set
+    next_refresh_ms: Option<u64>,
     /// Search engine for fuzzy finding
     search_engine: SearchEngine,
+    /// Search bar input
+    search_input: TextInput,
     /// New session creation component
     new_session_info: NewSessionInfo,
+    /// Directory to open as a new tab once its layout is picked
+    tab_directory: Option<String>,
     /// Current active screen
     active_screen: ActiveScreen,
     /// Error message to display
@@ -32,12 +58,65 @@ pub struct PluginState {
     previous_session_name: Option<String>,
     /// Request IDs for plugin communication
     request_ids: Vec<String>,
-    /// Selected index in main list (when not searching)
-    selected_index: Option<usize>,
-    /// Buffer for rename input
-    rename_buffer: String,
+    /// Selected item in main list (when not searching)
+    selection: Selection,
+    /// Rename input
+    rename_input: TextInput,
+    /// Session being renamed
+    rename_target: Option<RenameTarget>,
     /// Whether to show dead (resurrectable) sessions
     show_dead_sessions: bool,
+    /// Session names marked for batch operations
+    marked_sessions: BTreeSet<String>,
+    /// Resurrectable sessions the cleanup policy would delete (dry-run mode only)
+    cleanup_preview: Vec<String>,
+    /// Whether the main screen is in normal mode (vim keybinding preset)
+    normal_mode: bool,
+    /// Where the main list was last rendered
+    list_geometry: Option<ListGeometry>,
+    /// Where the layout list of the new session screen was last rendered
+    layout_list_geometry: Option<ListGeometry>,
+    /// List index and time (ms) of the last left click, for double-click detection
+    last_click: Option<(usize, u64)>,
+    /// Main list sections and folders collapsed to their header row
+    collapsed_groups: BTreeSet<(ItemGroup, Option<String>)>,
+    /// Directories read from each directory source, by source index
+    source_directories: BTreeMap<usize, Vec<ZoxideDirectory>>,
+    /// Outcome of the last read of each directory source, by source index
+    source_diagnostics: BTreeMap<usize, SourceDiagnostics>,
+    /// Git repository root of each known directory (None outside a repository)
+    git_roots: BTreeMap<String, Option<String>>,
+}
+
+/// Maximum time between two clicks on the same row to count as a double-click
+const DOUBLE_CLICK_MS: u64 = 500;
+
+/// Typing pause before the filesystem is searched for the search term
+const DISK_SEARCH_DELAY_MS: u64 = 400;
+
+/// Shortest search term searched for on disk
+const DISK_SEARCH_MIN_CHARS: usize = 2;
+
+/// A session selected for renaming
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameTarget {
+    /// The session this plugin instance runs in
+    Current(String),
+    /// Another live session
+    Live(String),
+    /// A resurrectable session
+    Resurrectable(String),
+}
+
+impl RenameTarget {
+    /// Get the current name of the session being renamed
+    pub fn name(&self) -> &str {
+        match self {
+            RenameTarget::Current(name)
+            | RenameTarget::Live(name)
+            | RenameTarget::Resurrectable(name) => name,
+        }
+    }
 }
 
 /// Represents the different screens in the plugin
@@ -50,12 +129,27 @@ pub enum ActiveScreen {
     NewSession,
     /// Session rename screen
     Rename,
+    /// Preview of resurrectable sessions exceeding the cleanup policy
+    CleanupPreview,
+    /// What was read from each directory source, including unparsable lines
+    Diagnostics,
+    /// Built-in directory browser picking the folder of a new session
+    Browse,
+    /// Layout selection for a directory opened as a new tab
+    TabLayout,
 }
 
 impl PluginState {
     /// Initialize plugin with configuration
     pub fn initialize(&mut self, configuration: BTreeMap<String, String>) {
         self.config = Config::from_zellij_config(&configuration);
+        self.session_manager
+            .set_protected_patterns(self.config.protected_sessions.clone());
+        self.normal_mode = self.config.keymap.has_normal_mode();
+        let errors = self.config.errors();
+        if !errors.is_empty() {
+            self.set_error(errors.join("; "));
+        }
     }
 
     /// Update session information with stability tracking
@@ -68,11 +162,13 @@ impl PluginState {
 
                 // Track session change for quick-switch
                 if self.current_session_name != new_current {
-                    // Request async read of previous session and MRU timestamps
+                    // Request async read of previous session, MRU timestamps and session directories
                     self.request_previous_session_read();
                     self.request_mru_timestamps_read();
+                    self.request_session_directories_read();
+                    self.request_collapsed_groups_read();
                     // Reset selection so it will be initialized to previous session
-                    self.selected_index = None;
+                    self.selection.clear();
                 }
 
                 self.current_session_name = new_current;
@@ -85,6 +181,7 @@ impl PluginState {
         // Use stable update that handles Zellij's inconsistent data
         let changed = self.session_manager.update_sessions_stable(sessions);
         if changed {
+            self.prune_marked_sessions();
             self.update_search_if_needed();
         }
         changed
@@ -100,14 +197,201 @@ impl PluginState {
             .session_manager
             .update_resurrectable_stable(resurrectable_sessions);
         if changed {
+            self.prune_marked_sessions();
             self.update_search_if_needed();
         }
         changed
     }
 
+    /// Delete dead sessions whose undo window closed while no plugin instance was running
+    pub fn finalize_abandoned_deletions(&mut self) {
+        self.session_manager.finalize_abandoned_deletions();
+    }
+
+    /// Apply the resurrectable session cleanup policy after a session update
+    /// In dry-run mode the affected sessions are only collected for the preview screen
+    /// Returns true if the displayed state changed
+    pub fn apply_cleanup_policy(&mut self) -> bool {
+        let policy = &self.config.resurrectable_cleanup;
+        let candidates = self.session_manager.cleanup_candidates(policy);
+
+        if policy.dry_run {
+            let changed = candidates != self.cleanup_preview;
+            self.cleanup_preview = candidates;
+            changed
+        } else if candidates.is_empty() {
+            false
+        } else {
+            self.session_manager.delete_dead_sessions(&candidates);
+            self.prune_marked_sessions();
+            self.update_search_if_needed();
+            true
+        }
+    }
+
+    /// Handle timer events
+    /// Returns true if the UI needs to be re-rendered
+    pub fn handle_timer(&mut self) -> bool {
+        let expired = self.session_manager.expire_undoable_deletion();
+
+        // Timers may fire slightly early, allow a small tolerance
+        let now = unix_now_ms();
+        if let Some((term, _)) = self
+            .pending_disk_search
+            .take_if(|(_, due)| now + 50 >= *due)
+        {
+            self.search_disk(&term);
+        }
+        if self.next_refresh_ms.is_none_or(|due| now + 250 < due) {
+            return expired;
+        }
+        // Directories are merged back in by identity, so selection and search are kept
+        self.fetch_directories();
+        self.session_manager.age_resurrectable_sessions();
+        self.apply_cleanup_policy();
+        self.schedule_refresh();
+        true
+    }
+
+    /// Search the filesystem for the search term once typing pauses,
+    /// if disk search is configured and nothing matches the term well
+    fn schedule_disk_search(&mut self) {
+        let term = self.search_engine.search_term();
+        if self.config.disk_search.roots.is_empty()
+            || term.chars().count() < DISK_SEARCH_MIN_CHARS
+            || self.search_engine.has_good_match()
+        {
+            self.pending_disk_search = None;
+            return;
+        }
+        self.pending_disk_search = Some((term.to_string(), unix_now_ms() + DISK_SEARCH_DELAY_MS));
+        set_timeout(DISK_SEARCH_DELAY_MS as f64 / 1000.0);
+    }
+
+    /// Run the filesystem search for a term under each configured root
+    /// Results of each root are added as they arrive
+    fn search_disk(&self, term: &str) {
+        let search = &self.config.disk_search;
+        for root in &search.roots {
+            let command =
+                zoxide::disk::search_command(term, root, search.max_depth, search.max_results);
+            let mut context = BTreeMap::new();
+            context.insert("zsm_disk_search".to_string(), term.to_string());
+            let command: Vec<&str> = command.iter().map(String::as_str).collect();
+            run_command(&command, context);
+        }
+    }
+
+    /// Add directories found on disk for a search term (ignored if the term changed since)
+    pub fn add_disk_search_results(&mut self, term: &str, output: &str) {
+        let paths = zoxide::disk::parse_output(output);
+        let items = self.combined_items();
+        self.search_engine.add_disk_directories(term, paths, &items);
+    }
+
+    /// Start the timer for the next periodic refresh, if `refresh_interval` is set
+    pub fn schedule_refresh(&mut self) {
+        self.next_refresh_ms = self.config.refresh_interval.map(|interval| {
+            set_timeout(interval.as_secs_f64());
+            unix_now_ms() + interval.as_millis() as u64
+        });
+    }
+
+    /// Get resurrectable sessions awaiting cleanup confirmation (for UI rendering)
+    pub fn cleanup_preview(&self) -> &[String] {
+        &self.cleanup_preview
+    }
+
+    /// Store the parsed output of a source and its diagnostics
+    pub fn set_source_output(&mut self, index: usize, command: String, result: ParseResult) {
+        let name = self
+            .config
+            .directory_sources
+            .get(index)
+            .map(DirectorySource::name)
+            .unwrap_or_default();
+        self.source_diagnostics.insert(
+            index,
+            SourceDiagnostics {
+                name,
+                command,
+                directories: result.directories.len(),
+                skipped: result.skipped,
+                error: None,
+            },
+        );
+        self.source_directories.insert(index, result.directories);
+    }
+
+    /// Record that a source failed, keeping the directories of its last successful read
+    /// Returns true if the source was not failing before, so the error should be shown
+    pub fn set_source_error(&mut self, index: usize, command: String, error: String) -> bool {
+        let name = self
+            .config
+            .directory_sources
+            .get(index)
+            .map(DirectorySource::name)
+            .unwrap_or_default();
+        let diagnostics = self.source_diagnostics.entry(index).or_default();
+        let newly_failing = diagnostics.error.is_none();
+        diagnostics.name = name;
+        diagnostics.command = command;
+        diagnostics.error = Some(error);
+        newly_failing
+    }
+
+    /// Directories of all sources read so far, merged in source order
+    pub fn merged_source_directories(&self) -> Vec<ZoxideDirectory> {
+        zoxide::source::merge(self.source_directories.values().map(Vec::as_slice))
+    }
+
+    /// Outcome of the last read of each directory source (for UI rendering)
+    pub fn source_diagnostics(&self) -> impl Iterator<Item = &SourceDiagnostics> {
+        self.source_diagnostics.values()
+    }
+
+    /// Number of source output lines that could not be parsed (for UI rendering)
+    pub fn skipped_line_count(&self) -> usize {
+        self.source_diagnostics
+            .values()
+            .map(|diagnostics| diagnostics.skipped.len())
+            .sum()
+    }
+
+    /// Show directories cached by a previous run until every directory source answers
+    pub fn load_cached_directories(&mut self, directories: Vec<ZoxideDirectory>) {
+        if self.directories_loaded {
+            return;
+        }
+        // Git roots are requested once fresh results arrive and commands can run
+        self.zoxide_directories = directories;
+        self.update_search_if_needed();
+    }
+
+    /// Stop showing cached directories (no source can answer)
+    pub fn mark_directories_loaded(&mut self) {
+        self.directories_loaded = true;
+    }
+
+    /// Record that a directory source answered, successfully or not
+    /// Returns true once every configured source has answered since startup
+    pub fn record_source_answer(&mut self, index: usize) -> bool {
+        self.answered_sources.insert(index);
+        if self.answered_sources.len() >= self.config.directory_sources.len() {
+            self.directories_loaded = true;
+        }
+        self.directories_loaded
+    }
+
+    /// Check whether every directory source has answered since startup (for UI rendering)
+    pub fn directories_loaded(&self) -> bool {
+        self.directories_loaded
+    }
+
     /// Update zoxide directories (managed separately from sessions)
     pub fn update_zoxide_directories(&mut self, directories: Vec<ZoxideDirectory>) {
         self.zoxide_directories = directories;
+        self.request_git_roots();
         self.update_search_if_needed();
     }
 
@@ -120,18 +404,18 @@ impl PluginState {
         }
 
         // Handle session deletion confirmation
-        if let Some(session_name) = self
-            .session_manager
-            .pending_deletion()
-            .map(|s| s.to_string())
-        {
-            return self.handle_deletion_confirmation(key, &session_name);
+        if !self.session_manager.pending_deletion().is_empty() {
+            return self.handle_deletion_confirmation(key);
         }
 
         match self.active_screen {
             ActiveScreen::Main => self.handle_main_screen_key(key),
             ActiveScreen::NewSession => self.handle_new_session_key(key),
             ActiveScreen::Rename => self.handle_rename_screen_key(key),
+            ActiveScreen::CleanupPreview => self.handle_cleanup_preview_key(key),
+            ActiveScreen::Diagnostics => self.handle_diagnostics_key(key),
+            ActiveScreen::Browse => self.handle_browser_key(key),
+            ActiveScreen::TabLayout => self.handle_tab_layout_key(key),
         }
     }
 
@@ -150,23 +434,88 @@ impl PluginState {
                 .map(|result| result.item.clone())
                 .collect()
         } else {
-            self.combined_items()
+            self.sectioned_items()
         }
     }
 
-    /// Combine sessions and zoxide directories for display
+    /// Combine sessions and zoxide directories, without section headers
     fn combined_items(&self) -> Vec<SessionItem> {
+        self.item_groups()
+            .into_iter()
+            .flat_map(|(_, items)| items)
+            .collect()
+    }
+
+    /// Combine sessions and zoxide directories under section headers,
+    /// leaving out the items of collapsed sections and empty sections entirely
+    /// With grouping enabled, items sharing a folder are nested under a folder header
+    /// after the items without one
+    fn sectioned_items(&self) -> Vec<SessionItem> {
+        let mut items = Vec::new();
+        for (group, group_items) in self.item_groups() {
+            if group_items.is_empty() {
+                continue;
+            }
+            let collapsed = self.collapsed_groups.contains(&(group, None));
+            items.push(SessionItem::Header {
+                group,
+                folder: None,
+                count: group_items.len(),
+                collapsed,
+            });
+            if collapsed {
+                continue;
+            }
+
+            let (ungrouped, folders) = grouping::nest(group_items, |item| self.folder_of(item));
+            items.extend(ungrouped);
+            for (folder, folder_items) in folders {
+                let collapsed = self
+                    .collapsed_groups
+                    .contains(&(group, Some(folder.clone())));
+                items.push(SessionItem::Header {
+                    group,
+                    folder: Some(folder),
+                    count: folder_items.len(),
+                    collapsed,
+                });
+                if !collapsed {
+                    items.extend(folder_items);
+                }
+            }
+        }
+        items
+    }
+
+    /// Get the folder an item is nested under (None when grouping is off or nothing matches)
+    fn folder_of(&self, item: &SessionItem) -> Option<String> {
+        let path = match item {
+            SessionItem::ExistingSession { directory, .. } => directory,
+            SessionItem::Directory { path, .. } => path,
+            SessionItem::ResurrectableSession { .. } | SessionItem::Header { .. } => return None,
+        };
+        match self.config.group_by {
+            GroupBy::None => None,
+            GroupBy::BasePath => {
+                grouping::base_path_of(path, &self.config.base_paths).map(str::to_string)
+            }
+            GroupBy::GitRoot => self.git_roots.get(path).cloned().flatten(),
+        }
+    }
+
+    /// Collect sessions and zoxide directories per list section
+    fn item_groups(&self) -> Vec<(ItemGroup, Vec<SessionItem>)> {
         let mut existing_sessions: Vec<SessionItem> = Vec::new();
         let mut resurrectable_sessions: Vec<SessionItem> = Vec::new();
         let mut directories: Vec<SessionItem> = Vec::new();
         let mut added_session_names = HashSet::new();
 
-        // First, collect existing sessions that match zoxide directories (including incremented ones)
+        // First, collect existing sessions that belong to a directory (recorded or matching zoxide)
         for session in self.session_manager.sessions() {
-            if let Some(zoxide_dir) = self.find_matching_zoxide_dir(&session.name) {
+            if let Some(directory) = self.find_session_directory(&session.name) {
                 existing_sessions.push(SessionItem::ExistingSession {
                     name: session.name.clone(),
-                    directory: zoxide_dir.directory.clone(),
+                    directory,
                     is_current: session.is_current_session,
                 });
                 added_session_names.insert(session.name.clone());
@@ -189,8 +538,8 @@ impl PluginState {
                     continue;
                 }
 
-                let matches_zoxide = self.find_matching_zoxide_dir(name).is_some();
-                if matches_zoxide || self.config.show_all_sessions {
+                let has_directory = self.find_session_directory(name).is_some();
+                if has_directory || self.config.show_all_sessions {
                     resurrectable_sessions.push(SessionItem::ResurrectableSession {
                         name: name.clone(),
                         duration: *duration,
@@ -204,6 +553,7 @@ impl PluginState {
             directories.push(SessionItem::Directory {
                 path: dir.directory.clone(),
                 session_name: dir.session_name.clone(),
+                vanished: dir.vanished,
             });
         }
 
@@ -236,11 +586,12 @@ impl PluginState {
             dur_a.cmp(&dur_b) // Ascending order (smallest/most recent first)
         });
 
-        // Combine: existing sessions, then resurrectable, then directories
-        let mut items = existing_sessions;
-        items.append(&mut resurrectable_sessions);
-        items.append(&mut directories);
-        items
+        // Sections: existing sessions, then resurrectable, then directories
+        vec![
+            (ItemGroup::Sessions, existing_sessions),
+            (ItemGroup::Resurrectable, resurrectable_sessions),
+            (ItemGroup::Directories, directories),
+        ]
     }
 
     /// Check if session name is an incremented version of base name
@@ -267,6 +618,18 @@ impl PluginState {
         })
     }
 
+    /// Find the directory a session belongs to
+    /// Prefers the directory recorded at creation/rename, falling back to zoxide name matching
+    fn find_session_directory(&self, session_name: &str) -> Option<String> {
+        self.session_manager
+            .session_directory(session_name)
+            .map(|directory| directory.to_string())
+            .or_else(|| {
+                self.find_matching_zoxide_dir(session_name)
+                    .map(|zoxide_dir| zoxide_dir.directory.clone())
+            })
+    }
+
     /// Get search engine (for UI rendering)
     pub fn search_engine(&self) -> &SearchEngine {
         &self.search_engine
@@ -288,11 +651,14 @@ impl PluginState {
         if self.search_engine.is_searching() {
             self.search_engine.selected_index()
         } else {
+            let items = self.display_items();
             // Initialize selection to previous session if not set
-            if self.selected_index.is_none() {
-                self.selected_index = self.find_previous_session_index();
+            if self.selection.is_none() {
+                if let Some(index) = self.find_previous_session_index() {
+                    self.selection.select(&items, index);
+                }
             }
-            self.selected_index
+            self.selection.resolve(&items)
         }
     }
 
@@ -308,6 +674,11 @@ impl PluginState {
         })
     }
 
+    /// Whether any zoxide directory no longer exists (for UI rendering)
+    pub fn has_vanished_directories(&self) -> bool {
+        self.zoxide_directories.iter().any(|dir| dir.vanished)
+    }
+
     /// Get colors
     pub fn colors(&self) -> Option<Palette> {
         self.colors
@@ -333,9 +704,44 @@ impl PluginState {
         &self.config
     }
 
-    /// Get rename buffer (for UI rendering)
-    pub fn rename_buffer(&self) -> &str {
-        &self.rename_buffer
+    /// Check if a session is marked for batch operations (for UI rendering)
+    pub fn is_marked(&self, session_name: &str) -> bool {
+        self.marked_sessions.contains(session_name)
+    }
+
+    /// Get number of marked sessions (for UI rendering)
+    pub fn marked_count(&self) -> usize {
+        self.marked_sessions.len()
+    }
+
+    /// Get the active keymap (for UI rendering)
+    pub fn keymap(&self) -> &Keymap {
+        &self.config.keymap
+    }
+
+    /// Whether the main screen is in normal mode (for UI rendering)
+    pub fn is_normal_mode(&self) -> bool {
+        self.normal_mode
+    }
+
+    /// Record where the main list was rendered (for mouse handling)
+    pub fn set_list_geometry(&mut self, geometry: Option<ListGeometry>) {
+        self.list_geometry = geometry;
+    }
+
+    /// Record where the layout list was rendered (for mouse handling)
+    pub fn set_layout_list_geometry(&mut self, geometry: Option<ListGeometry>) {
+        self.layout_list_geometry = geometry;
+    }
+
+    /// Get rename input (for UI rendering)
+    pub fn rename_input(&self) -> &TextInput {
+        &self.rename_input
+    }
+
+    /// Get search bar input (for UI rendering)
+    pub fn search_input(&self) -> &TextInput {
+        &self.search_input
     }
 
     /// Get selected item
@@ -344,215 +750,857 @@ impl PluginState {
             self.search_engine.selected_item().cloned()
         } else {
             let items = self.display_items();
-            self.selected_index.and_then(|i| items.get(i).cloned())
+            self.selection
+                .find(&items)
+                .and_then(|i| items.get(i).cloned())
         }
     }
 
     /// Handle main screen key input
     fn handle_main_screen_key(&mut self, key: KeyWithModifier) -> bool {
-        match key.bare_key {
-            BareKey::Up if key.has_no_modifiers() => {
+        const MAIN_ACTIONS: [Action; 29] = [
+            Action::NormalMode,
+            Action::InsertMode,
+            Action::Up,
+            Action::Down,
+            Action::PageUp,
+            Action::PageDown,
+            Action::First,
+            Action::Last,
+            Action::Switch,
+            Action::QuickCreate,
+            Action::Kill,
+            Action::Mark,
+            Action::MarkAll,
+            Action::Undo,
+            Action::Back,
+            Action::Exit,
+            Action::Reload,
+            Action::Rename,
+            Action::ToggleDead,
+            Action::Protect,
+            Action::Cleanup,
+            Action::ToggleGroup,
+            Action::Prune,
+            Action::PruneAll,
+            Action::Diagnostics,
+            Action::OpenTab,
+            Action::OpenTabLayout,
+            Action::OpenPane,
+            Action::OpenFloating,
+        ];
+
+        let action = self.config.keymap.main_action_for(
+            &key,
+            &MAIN_ACTIONS,
+            self.normal_mode,
+            !self.search_input.is_empty(),
+        );
+        let Some(action) = action else {
+            if let Some(position) = Self::quick_select_position(&key) {
+                return self.quick_select(position);
+            }
+            // Unbound keys only edit the search bar outside of normal mode
+            return !self.normal_mode && self.handle_search_input_key(key);
+        };
+
+        match action {
+            Action::NormalMode => {
+                self.normal_mode = true;
+                true
+            }
+            Action::InsertMode => {
+                self.normal_mode = false;
+                true
+            }
+            Action::Up => {
                 self.move_selection_up();
                 true
             }
-            BareKey::Down if key.has_no_modifiers() => {
+            Action::Down => {
                 self.move_selection_down();
                 true
             }
-            BareKey::Enter if key.has_no_modifiers() => {
+            Action::PageUp => {
+                let current = self.selected_index().unwrap_or(0);
+                self.select_index(current.saturating_sub(self.page_size()));
+                true
+            }
+            Action::PageDown => {
+                let current = self.selected_index().unwrap_or(0);
+                self.select_index(current + self.page_size());
+                true
+            }
+            Action::First => {
+                self.select_index(0);
+                true
+            }
+            Action::Last => {
+                self.select_index(usize::MAX);
+                true
+            }
+            Action::Switch => {
                 self.handle_item_selection();
                 true
             }
-            BareKey::Enter if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+            Action::QuickCreate => {
                 self.handle_quick_session_creation();
                 true
             }
-            BareKey::Delete if key.has_no_modifiers() => {
+            Action::Kill => {
                 self.handle_delete_key();
                 true
             }
-            BareKey::Char(c) if key.has_no_modifiers() && c != '\n' => {
-                let items = self.combined_items(); // Always use full item list, not search results
-                self.search_engine.add_char(c, &items);
+            Action::Mark => {
+                self.toggle_mark_selected();
                 true
             }
-            BareKey::Backspace if key.has_no_modifiers() => {
-                let items = self.combined_items(); // Always use full item list, not search results
-                self.search_engine.backspace(&items);
+            Action::MarkAll => {
+                self.toggle_mark_all_visible();
+                true
+            }
+            Action::Undo => {
+                // Undo the most recent kill/delete while the undo window is open
+                if let Some(unrestorable) = self.session_manager.undo_deletion() {
+                    if !unrestorable.is_empty() {
+                        self.set_error(format!(
+                            "Could not undo killing {}: not saved as resurrectable yet",
+                            unrestorable.join(", ")
+                        ));
+                    }
+                    self.update_search_if_needed();
+                }
                 true
             }
-            BareKey::Esc if key.has_no_modifiers() => {
+            Action::Back => {
                 if self.search_engine.is_searching() {
+                    self.search_input.clear();
                     self.search_engine.clear();
                     true
+                } else if !self.marked_sessions.is_empty() {
+                    self.marked_sessions.clear();
+                    true
                 } else {
                     hide_self();
                     false
                 }
             }
-            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+            Action::Exit => {
                 hide_self();
                 false
             }
-            BareKey::Char('r') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+            Action::Reload => {
                 // reload zoxide directories
-                self.fetch_zoxide_directories();
+                self.fetch_directories();
                 true
             }
-            BareKey::Char('r') if key.has_modifiers(&[KeyModifier::Alt]) => {
-                // Rename current session
-                self.rename_buffer = self.current_session_name.clone().unwrap_or_default();
-                self.active_screen = ActiveScreen::Rename;
+            Action::Rename => {
+                // Rename selected session (or current session when a directory is selected)
+                self.start_rename();
                 true
             }
-            BareKey::Char('d') if key.has_modifiers(&[KeyModifier::Alt]) => {
+            Action::ToggleDead => {
                 // Toggle dead (resurrectable) sessions visibility
                 self.show_dead_sessions = !self.show_dead_sessions;
                 true
             }
+            Action::Protect => {
+                // Toggle deletion protection of the selected session
+                self.toggle_protect_selected();
+                true
+            }
+            Action::ToggleGroup => {
+                self.toggle_selected_group();
+                true
+            }
+            Action::Diagnostics => {
+                self.active_screen = ActiveScreen::Diagnostics;
+                true
+            }
+            Action::OpenTab => {
+                self.open_directory_in_tab();
+                true
+            }
+            Action::OpenTabLayout => {
+                self.pick_tab_layout();
+                true
+            }
+            Action::OpenPane => {
+                self.open_directory_in_pane(false);
+                true
+            }
+            Action::OpenFloating => {
+                self.open_directory_in_pane(true);
+                true
+            }
+            Action::Prune => {
+                self.prune_selected_directory();
+                true
+            }
+            Action::PruneAll => {
+                self.prune_vanished_directories();
+                true
+            }
+            Action::Cleanup => {
+                // Review resurrectable sessions exceeding the cleanup policy
+                if self.cleanup_preview.is_empty() {
+                    self.set_error(
+                        "No resurrectable sessions exceed the cleanup policy".to_string(),
+                    );
+                } else {
+                    self.active_screen = ActiveScreen::CleanupPreview;
+                }
+                true
+            }
             _ => false,
         }
     }
 
-    /// Handle new session screen key input
-    fn handle_new_session_key(&mut self, key: KeyWithModifier) -> bool {
+    /// Visible row (0-8) picked with `Alt+1`..`Alt+9`
+    fn quick_select_position(key: &KeyWithModifier) -> Option<usize> {
         match key.bare_key {
-            BareKey::Enter if key.has_no_modifiers() => {
-                // Only return to Main if we were in layout selection (session created)
-                // If in name entry, handle_selection just advances to layout selection
-                let was_in_layout_selection = self.new_session_info.entering_layout_search_term();
-                self.new_session_info
-                    .handle_selection(&self.current_session_name);
-                if was_in_layout_selection {
-                    self.active_screen = ActiveScreen::Main;
-                }
+            BareKey::Char(c @ '1'..='9') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                c.to_digit(10).map(|digit| digit as usize - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Switch to (or create a session for) the item on a visible row
+    fn quick_select(&mut self, position: usize) -> bool {
+        let Some(geometry) = self.list_geometry else {
+            return false;
+        };
+        if position >= geometry.len {
+            return false;
+        }
+        self.select_index(geometry.first_index + position);
+        self.handle_item_selection();
+        true
+    }
+
+    /// Number of list rows visible at once
+    fn page_size(&self) -> usize {
+        self.list_geometry
+            .map(|geometry| geometry.len)
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// Pass a key to the search bar and re-run the search if the term changed
+    fn handle_search_input_key(&mut self, key: KeyWithModifier) -> bool {
+        if !self.search_input.handle_key(&key) {
+            return false;
+        }
+        if self.search_input.text() != self.search_engine.search_term() {
+            let term = self.search_input.text().to_string();
+            let items = self.combined_items(); // Always use full item list, not search results
+            self.search_engine.update_search(term, &items);
+            self.schedule_disk_search();
+        }
+        true
+    }
+
+    /// Handle cleanup preview screen key input
+    fn handle_cleanup_preview_key(&mut self, key: KeyWithModifier) -> bool {
+        let keymap = &self.config.keymap;
+        match key.bare_key {
+            _ if keymap.matches(Action::Confirm, &key, false) => {
+                self.delete_cleanup_preview();
+                true
+            }
+            BareKey::Char('y') if key.has_no_modifiers() => {
+                self.delete_cleanup_preview();
+                true
+            }
+            _ if keymap.matches(Action::Cancel, &key, false) => {
+                self.active_screen = ActiveScreen::Main;
+                true
+            }
+            BareKey::Char('n') if key.has_no_modifiers() => {
+                self.active_screen = ActiveScreen::Main;
                 true
             }
-            BareKey::Enter if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+            _ => false,
+        }
+    }
+
+    /// Handle key input on the diagnostics screen (any confirm or cancel key closes it)
+    fn handle_diagnostics_key(&mut self, key: KeyWithModifier) -> bool {
+        let keymap = &self.config.keymap;
+        if keymap.matches(Action::Confirm, &key, false)
+            || keymap.matches(Action::Cancel, &key, false)
+        {
+            self.active_screen = ActiveScreen::Main;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Delete the resurrectable sessions listed in the cleanup preview
+    fn delete_cleanup_preview(&mut self) {
+        let candidates = std::mem::take(&mut self.cleanup_preview);
+        self.session_manager.delete_dead_sessions(&candidates);
+        self.prune_marked_sessions();
+        self.update_search_if_needed();
+        self.active_screen = ActiveScreen::Main;
+    }
+
+    /// Handle key input while picking the layout of a directory opened as a new tab
+    fn handle_tab_layout_key(&mut self, key: KeyWithModifier) -> bool {
+        const TAB_LAYOUT_ACTIONS: [Action; 4] =
+            [Action::Confirm, Action::Cancel, Action::Up, Action::Down];
+
+        let action = self
+            .config
+            .keymap
+            .action_for(&key, &TAB_LAYOUT_ACTIONS, false);
+        match action {
+            Some(Action::Confirm) => self.open_tab_with_selected_layout(),
+            Some(Action::Cancel) => {
+                // Clear the layout search first, then go back to the list
+                if self.new_session_info.is_searching() {
+                    self.new_session_info.handle_escape();
+                } else {
+                    self.close_tab_layout();
+                }
+            }
+            Some(Action::Up) => self.new_session_info.move_selection_up(),
+            Some(Action::Down) => self.new_session_info.move_selection_down(),
+            _ => match key.bare_key {
+                BareKey::Backspace if key.has_no_modifiers() => {
+                    self.new_session_info.handle_backspace()
+                }
+                BareKey::Char(character) if key.has_no_modifiers() => {
+                    self.new_session_info.add_char(character)
+                }
+                _ => return false,
+            },
+        }
+        true
+    }
+
+    /// Handle new session screen key input
+    fn handle_new_session_key(&mut self, key: KeyWithModifier) -> bool {
+        if self.new_session_info.folder_entry.is_some() {
+            return self.handle_folder_entry_key(key);
+        }
+
+        const NEW_SESSION_ACTIONS: [Action; 10] = [
+            Action::Confirm,
+            Action::QuickCreate,
+            Action::Cancel,
+            Action::PickFolder,
+            Action::BrowseFolder,
+            Action::ClearFolder,
+            Action::EditFolder,
+            Action::EditName,
+            Action::Up,
+            Action::Down,
+        ];
+
+        let action = self
+            .config
+            .keymap
+            .action_for(&key, &NEW_SESSION_ACTIONS, false);
+        match action {
+            Some(Action::Confirm) => self.confirm_new_session(),
+            Some(Action::QuickCreate) => {
                 // Quick session creation with default layout
                 if self.new_session_info.name().len() >= 108 {
                     self.set_error("Session name must be shorter than 108 bytes".to_string());
                 } else if self.new_session_info.name().contains('/') {
                     self.set_error("Session name cannot contain '/'".to_string());
                 } else {
+                    self.record_new_session_directory();
                     self.new_session_info.handle_quick_session_creation(
                         &self.current_session_name,
                         &self.config.default_layout,
                     );
-                    self.active_screen = ActiveScreen::Main;
+                    self.feed_new_session_directory();
+                    self.close_new_session_screen();
                 }
-                true
             }
-            BareKey::Esc if key.has_no_modifiers() => {
-                // Special handling for Esc when entering session name - go back to main
+            Some(Action::Cancel) => {
+                // Special handling when entering session name - go back to main
                 if self.new_session_info.entering_new_session_name()
                     && self.new_session_info.name().is_empty()
                 {
-                    self.active_screen = ActiveScreen::Main;
+                    self.close_new_session_screen();
                 } else {
                     // Let NewSessionInfo handle its own escape logic
-                    self.new_session_info.handle_key(key);
+                    self.new_session_info.handle_escape();
                 }
-                true
             }
-            BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
-                // Handle filepicker
-                self.launch_filepicker();
-                true
+            Some(Action::PickFolder) => {
+                if self.config.folder_picker == FolderPicker::Builtin {
+                    self.open_browser();
+                } else {
+                    self.launch_filepicker();
+                }
             }
-            BareKey::Char('c') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+            Some(Action::BrowseFolder) => self.open_browser(),
+            Some(Action::ClearFolder) => {
                 // Clear session folder - don't delegate to NewSessionInfo
                 self.new_session_info.set_folder(None);
-                true
             }
+            Some(Action::EditFolder) => {
+                let folder = self
+                    .new_session_info
+                    .new_session_folder()
+                    .map(|folder| folder.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.new_session_info.folder_entry = Some(PathEntry::new(&folder));
+            }
+            Some(Action::EditName) => {
+                // Correct session name - only when in layout search mode
+                if self.new_session_info.entering_layout_search_term() {
+                    self.new_session_info.correct_session_name();
+                }
+            }
+            Some(Action::Up) => self.new_session_info.move_selection_up(),
+            Some(Action::Down) => self.new_session_info.move_selection_down(),
             _ => {
                 // Delegate other keys to NewSessionInfo component
                 self.new_session_info.handle_key(key);
-                true
             }
         }
+        true
     }
 
-    /// Handle rename screen key input
-    fn handle_rename_screen_key(&mut self, key: KeyWithModifier) -> bool {
-        match key.bare_key {
-            BareKey::Enter if key.has_no_modifiers() => {
-                // Validate and apply rename
-                let new_name = self.rename_buffer.trim();
-                if new_name.is_empty() {
-                    self.set_error("Session name cannot be empty".to_string());
-                } else if new_name.len() >= 108 {
-                    self.set_error("Session name must be shorter than 108 bytes".to_string());
-                } else if new_name.contains('/') {
-                    self.set_error("Session name cannot contain '/'".to_string());
+    /// Handle key input while typing the folder of a new session
+    fn handle_folder_entry_key(&mut self, key: KeyWithModifier) -> bool {
+        const FOLDER_ENTRY_ACTIONS: [Action; 4] =
+            [Action::Confirm, Action::Cancel, Action::Up, Action::Down];
+
+        let Some(entry) = self.new_session_info.folder_entry.as_mut() else {
+            return false;
+        };
+        match self
+            .config
+            .keymap
+            .action_for(&key, &FOLDER_ENTRY_ACTIONS, false)
+        {
+            Some(Action::Confirm) => {
+                let path =
+                    path_entry::expand_tilde(entry.chosen().trim(), self.home_dir.as_deref());
+                let folder = std::path::PathBuf::from(path.trim_end_matches('/'))
+                    .components()
+                    .collect::<std::path::PathBuf>();
+                if folder.is_absolute() && Self::host_path(&folder).is_dir() {
+                    self.new_session_info.set_folder(Some(folder));
+                    self.new_session_info.folder_entry = None;
                 } else {
-                    // Optimistic update: rename in local state immediately for instant UI feedback
-                    if let Some(ref old_name) = self.current_session_name {
-                        self.session_manager
-                            .rename_session_in_local_state(old_name, new_name);
-                    }
-                    // Call Zellij's rename_session API
-                    rename_session(new_name);
-                    self.current_session_name = Some(new_name.to_string());
-                    self.rename_buffer.clear();
-                    self.active_screen = ActiveScreen::Main;
+                    self.set_error(format!("Not a directory: {}", path));
                 }
-                true
             }
-            BareKey::Esc if key.has_no_modifiers() => {
-                // Cancel rename
-                self.rename_buffer.clear();
-                self.active_screen = ActiveScreen::Main;
-                true
+            Some(Action::Cancel) => self.new_session_info.folder_entry = None,
+            Some(Action::Up) => entry.move_up(),
+            Some(Action::Down) => entry.move_down(),
+            _ if key.bare_key == BareKey::Tab && key.has_no_modifiers() => {
+                let typed = entry.chosen().to_string();
+                let (completed, candidates) =
+                    path_entry::complete(&typed, self.home_dir.as_deref(), |directory| {
+                        Self::read_subdirectories(std::path::Path::new(directory))
+                            .unwrap_or_default()
+                    });
+                entry.set_text(&completed);
+                let suggestions = if candidates.is_empty() {
+                    self.folder_suggestions(&completed)
+                } else {
+                    candidates
+                };
+                if let Some(entry) = self.new_session_info.folder_entry.as_mut() {
+                    entry.set_suggestions(suggestions);
+                }
             }
-            BareKey::Char(c) if key.has_no_modifiers() && c != '\n' => {
-                // Add character to buffer
-                self.rename_buffer.push(c);
-                true
+            _ => {
+                if !entry.input_mut().handle_key(&key) {
+                    return false;
+                }
+                let typed = entry.input().text().to_string();
+                let suggestions = self.folder_suggestions(&typed);
+                if let Some(entry) = self.new_session_info.folder_entry.as_mut() {
+                    entry.set_suggestions(suggestions);
+                }
             }
-            BareKey::Backspace if key.has_no_modifiers() => {
-                // Remove last character
-                self.rename_buffer.pop();
-                true
+        }
+        true
+    }
+
+    /// Zoxide directories containing a typed folder path
+    fn folder_suggestions(&self, typed: &str) -> Vec<String> {
+        path_entry::suggestions(
+            typed,
+            self.home_dir.as_deref(),
+            self.zoxide_directories
+                .iter()
+                .filter(|dir| !dir.vanished)
+                .map(|dir| dir.directory.as_str()),
+        )
+    }
+
+    /// Advance from name entry to layout selection, or create the session
+    fn confirm_new_session(&mut self) {
+        // Only return to Main if we were in layout selection (session created)
+        // If in name entry, handle_selection just advances to layout selection
+        let was_in_layout_selection = self.new_session_info.entering_layout_search_term();
+        if was_in_layout_selection {
+            self.record_new_session_directory();
+        }
+        let folder = self
+            .new_session_info
+            .new_session_folder()
+            .map(|folder| folder.to_string_lossy().to_string());
+        self.new_session_info
+            .handle_selection(&self.current_session_name);
+        if was_in_layout_selection {
+            if let Some(folder) = folder {
+                self.feed_created_session(&folder);
             }
-            _ => false,
+            self.close_new_session_screen();
         }
     }
 
+    /// Go back to the main screen, forgetting filepickers launched from the new session screen
+    /// (a folder they answer with later is ignored)
+    fn close_new_session_screen(&mut self) {
+        self.request_ids.clear();
+        self.active_screen = ActiveScreen::Main;
+    }
+
+    /// Handle mouse input
+    pub fn handle_mouse(&mut self, mouse: Mouse) -> bool {
+        // Dialogs and errors are keyboard-only
+        if self.error.is_some() || !self.session_manager.pending_deletion().is_empty() {
+            return false;
+        }
+
+        match self.active_screen {
+            ActiveScreen::Main => self.handle_main_screen_mouse(mouse),
+            ActiveScreen::NewSession if self.new_session_info.entering_layout_search_term() => {
+                self.handle_layout_list_mouse(mouse)
+            }
+            ActiveScreen::TabLayout => self.handle_layout_list_mouse(mouse),
+            _ => false,
+        }
+    }
+
+    /// Handle mouse input on the main list: click selects, double-click switches/creates
+    fn handle_main_screen_mouse(&mut self, mouse: Mouse) -> bool {
+        let len = self.display_items().len();
+        if len == 0 {
+            return false;
+        }
+        let current = self.selected_index().unwrap_or(0);
+
+        match mouse {
+            Mouse::LeftClick(line, _) => {
+                let Some(index) = self.clicked_index(self.list_geometry, line) else {
+                    return false;
+                };
+                self.select_index(index);
+                if self.is_double_click(index) {
+                    self.handle_item_selection();
+                }
+                true
+            }
+            Mouse::ScrollUp(lines) => {
+                self.select_index(current.saturating_sub(lines.max(1)));
+                true
+            }
+            Mouse::ScrollDown(lines) => {
+                self.select_index((current + lines.max(1)).min(len - 1));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handle mouse input on the layout list: click selects, double-click creates the session
+    /// (or opens the tab)
+    fn handle_layout_list_mouse(&mut self, mouse: Mouse) -> bool {
+        let current = self.new_session_info.selected_layout_index();
+
+        match mouse {
+            Mouse::LeftClick(line, _) => {
+                let Some(index) = self.clicked_index(self.layout_list_geometry, line) else {
+                    return false;
+                };
+                self.new_session_info.select_layout(index);
+                if self.is_double_click(index) {
+                    if self.active_screen == ActiveScreen::TabLayout {
+                        self.open_tab_with_selected_layout();
+                    } else {
+                        self.confirm_new_session();
+                    }
+                }
+                true
+            }
+            Mouse::ScrollUp(lines) => {
+                self.new_session_info
+                    .select_layout(current.saturating_sub(lines.max(1)));
+                true
+            }
+            Mouse::ScrollDown(lines) => {
+                self.new_session_info.select_layout(current + lines.max(1));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// List index of the row clicked on a screen line, if any
+    fn clicked_index(&self, geometry: Option<ListGeometry>, line: isize) -> Option<usize> {
+        let line = usize::try_from(line).ok()?;
+        geometry?.index_at(line)
+    }
+
+    /// Record a click on a list index and report whether it completes a double-click
+    fn is_double_click(&mut self, index: usize) -> bool {
+        let now = unix_now_ms();
+        let is_double = matches!(
+            self.last_click,
+            Some((last_index, last_ms)) if last_index == index && now.saturating_sub(last_ms) <= DOUBLE_CLICK_MS
+        );
+        // A double-click consumes the click so a third click starts over
+        self.last_click = if is_double { None } else { Some((index, now)) };
+        is_double
+    }
+
+    /// Open the rename screen for the selected session
+    /// Falls back to the current session when no session is selected
+    fn start_rename(&mut self) {
+        let target = match self.selected_item() {
+            Some(SessionItem::ExistingSession { name, .. })
+                if Some(&name) != self.current_session_name.as_ref() =>
+            {
+                Some(RenameTarget::Live(name))
+            }
+            Some(SessionItem::ResurrectableSession { name, .. }) => {
+                Some(RenameTarget::Resurrectable(name))
+            }
+            _ => self.current_session_name.clone().map(RenameTarget::Current),
+        };
+
+        if let Some(target) = target {
+            self.rename_input.set_text(target.name());
+            self.rename_target = Some(target);
+            self.active_screen = ActiveScreen::Rename;
+        }
+    }
+
+    /// Get the session being renamed (for UI rendering)
+    pub fn rename_target(&self) -> Option<&RenameTarget> {
+        self.rename_target.as_ref()
+    }
+
+    /// Handle rename screen key input
+    fn handle_rename_screen_key(&mut self, key: KeyWithModifier) -> bool {
+        let keymap = &self.config.keymap;
+        match key.bare_key {
+            _ if keymap.matches(Action::Confirm, &key, false) => {
+                // Validate and apply rename
+                let new_name = self.rename_input.text().trim().to_string();
+                let old_name = self
+                    .rename_target
+                    .as_ref()
+                    .map(|target| target.name().to_string());
+                if new_name.is_empty() {
+                    self.set_error("Session name cannot be empty".to_string());
+                } else if new_name.len() >= 108 {
+                    self.set_error("Session name must be shorter than 108 bytes".to_string());
+                } else if new_name.contains('/') {
+                    self.set_error("Session name cannot contain '/'".to_string());
+                } else if old_name.as_deref() == Some(new_name.as_str()) {
+                    // Unchanged name - nothing to do
+                    self.close_rename_screen();
+                } else if self.session_manager.session_name_exists(&new_name) {
+                    self.set_error(format!("A session named '{}' already exists", new_name));
+                } else {
+                    if let Some(target) = self.rename_target.clone() {
+                        self.apply_rename(&target, &new_name);
+                    }
+                    self.close_rename_screen();
+                }
+                true
+            }
+            _ if keymap.matches(Action::Cancel, &key, false) => {
+                // Cancel rename
+                self.close_rename_screen();
+                true
+            }
+            _ => self.rename_input.handle_key(&key),
+        }
+    }
+
+    /// Leave the rename screen and reset its state
+    fn close_rename_screen(&mut self) {
+        self.rename_input.clear();
+        self.rename_target = None;
+        self.active_screen = ActiveScreen::Main;
+    }
+
+    /// Rename a session and carry its MRU timestamp, previous-session pointer
+    /// and directory association over to the new name
+    fn apply_rename(&mut self, target: &RenameTarget, new_name: &str) {
+        let old_name = target.name();
+
+        match target {
+            RenameTarget::Current(_) => {
+                // Call Zellij's rename_session API
+                rename_session(new_name);
+                self.current_session_name = Some(new_name.to_string());
+            }
+            RenameTarget::Live(_) => {
+                // The plugin API can only rename its own session, so go through the CLI;
+                // its state is carried over once the rename succeeded
+                let mut context = BTreeMap::new();
+                context.insert("zsm_rename".to_string(), old_name.to_string());
+                context.insert("zsm_rename_to".to_string(), new_name.to_string());
+                run_command(
+                    &[
+                        "zellij",
+                        "--session",
+                        old_name,
+                        "action",
+                        "rename-session",
+                        new_name,
+                    ],
+                    context,
+                );
+                return;
+            }
+            RenameTarget::Resurrectable(_) => {
+                // Resurrectable sessions only exist as serialized layouts in Zellij's cache;
+                // their state is carried over once the move succeeded
+                let mut context = BTreeMap::new();
+                context.insert("zsm_rename".to_string(), old_name.to_string());
+                context.insert("zsm_rename_to".to_string(), new_name.to_string());
+                run_command(
+                    &[
+                        "sh",
+                        "-c",
+                        "moved=; for d in \"${XDG_CACHE_HOME:-$HOME/.cache}\"/zellij/*/session_info \
+                         \"$HOME/Library/Caches/org.Zellij-Contributors.Zellij\"/*/session_info; do \
+                         if [ -d \"$d/$1\" ] && [ ! -e \"$d/$2\" ]; then mv \"$d/$1\" \"$d/$2\" && moved=1; fi; done; \
+                         [ -n \"$moved\" ] || { echo \"no cached session '$1' could be moved to '$2'\" >&2; exit 1; }",
+                        "sh",
+                        old_name,
+                        new_name,
+                    ],
+                    context,
+                );
+                return;
+            }
+        }
+
+        // The current session is renamed through the plugin API, which reports no result
+        self.finish_rename(old_name, new_name);
+    }
+
+    /// Carry the state of a renamed session (MRU, directory, protection, marks) over to its new name
+    pub fn finish_rename(&mut self, old_name: &str, new_name: &str) {
+        self.session_manager
+            .rename_session_in_local_state(old_name, new_name);
+
+        // Persist the carried-over state so other plugin instances see it too, and forget
+        // the old name so a later session created under it starts fresh
+        let timestamp = self.session_manager.get_mru_rank(new_name);
+        if timestamp > 0 {
+            Self::write_mru_timestamp(new_name, timestamp);
+            Self::write_mru_timestamp(old_name, 0);
+        }
+        if let Some(directory) = self.session_manager.session_directory(new_name) {
+            Self::write_session_directory(new_name, directory);
+            Self::write_session_directory(old_name, "");
+        }
+        if self.previous_session_name.as_deref() == Some(old_name) {
+            self.previous_session_name = Some(new_name.to_string());
+            Self::write_previous_session(new_name);
+        }
+        if self.marked_sessions.remove(old_name) {
+            self.marked_sessions.insert(new_name.to_string());
+        }
+
+        self.update_search_if_needed();
+    }
+
     /// Handle deletion confirmation
-    fn handle_deletion_confirmation(&mut self, key: KeyWithModifier, _session_name: &str) -> bool {
+    fn handle_deletion_confirmation(&mut self, key: KeyWithModifier) -> bool {
+        if self.session_manager.typed_confirmation_target().is_some() {
+            return self.handle_typed_deletion_confirmation(key);
+        }
+
         match key.bare_key {
             BareKey::Char('y') | BareKey::Char('Y') if key.has_no_modifiers() => {
-                self.session_manager.confirm_deletion();
+                self.confirm_deletion();
+                true
+            }
+            BareKey::Char('n') | BareKey::Char('N') if key.has_no_modifiers() => {
+                self.session_manager.cancel_deletion();
+                true
+            }
+            _ if self.config.keymap.matches(Action::Cancel, &key, false) => {
+                self.session_manager.cancel_deletion();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handle deletion confirmation of a protected session, which requires typing its name
+    fn handle_typed_deletion_confirmation(&mut self, key: KeyWithModifier) -> bool {
+        let keymap = &self.config.keymap;
+        match key.bare_key {
+            _ if keymap.matches(Action::Confirm, &key, false) => {
+                if self.session_manager.can_confirm_deletion() {
+                    self.confirm_deletion();
+                }
                 true
             }
-            BareKey::Char('n') | BareKey::Char('N') | BareKey::Esc if key.has_no_modifiers() => {
+            _ if keymap.matches(Action::Cancel, &key, false) => {
                 self.session_manager.cancel_deletion();
                 true
             }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                self.session_manager.pop_typed_confirmation();
+                true
+            }
+            BareKey::Char(c) if key.has_no_modifiers() && c != '\n' => {
+                self.session_manager.push_typed_confirmation(c);
+                true
+            }
             _ => false,
         }
     }
 
+    /// Confirm the pending deletion
+    fn confirm_deletion(&mut self) {
+        self.session_manager.confirm_deletion();
+        self.marked_sessions.clear();
+        // Wake up when the undo window closes to finalize the deletion
+        set_timeout(UNDO_WINDOW_SECS as f64);
+    }
+
+    /// Select the item at an index of the displayed list
+    fn select_index(&mut self, index: usize) {
+        if self.search_engine.is_searching() {
+            self.search_engine.select_index(index);
+        } else {
+            let items = self.display_items();
+            self.selection.select(&items, index);
+        }
+    }
+
     /// Move selection up
     fn move_selection_up(&mut self) {
         if self.search_engine.is_searching() {
             self.search_engine.move_selection_up();
         } else {
-            let items_len = self.display_items().len();
-            if items_len == 0 {
-                return;
-            }
-
-            if let Some(selected) = self.selected_index.as_mut() {
-                if *selected == 0 {
-                    *selected = items_len.saturating_sub(1);
-                } else {
-                    *selected = selected.saturating_sub(1);
-                }
-            } else {
-                self.selected_index = Some(items_len.saturating_sub(1));
-            }
+            let items = self.display_items();
+            self.selection.move_up(&items);
         }
     }
 
@@ -561,32 +1609,46 @@ impl PluginState {
         if self.search_engine.is_searching() {
             self.search_engine.move_selection_down();
         } else {
-            let items_len = self.display_items().len();
-            if items_len == 0 {
-                return;
-            }
-
-            if let Some(selected) = self.selected_index.as_mut() {
-                if *selected == items_len.saturating_sub(1) {
-                    *selected = 0;
-                } else {
-                    *selected += 1;
-                }
-            } else {
-                self.selected_index = Some(0);
-            }
+            let items = self.display_items();
+            self.selection.move_down(&items);
         }
     }
 
     /// Handle item selection (Enter key)
     fn handle_item_selection(&mut self) {
+        // Section and folder headers expand or collapse their items
+        match self.selected_item() {
+            // Search results are not sectioned, the header of directories found on disk is a label
+            Some(SessionItem::Header { .. }) if self.search_engine.is_searching() => return,
+            Some(SessionItem::Header { group, folder, .. }) => {
+                self.toggle_group(group, folder);
+                return;
+            }
+            Some(SessionItem::Directory {
+                path,
+                vanished: true,
+                ..
+            }) => {
+                self.set_error(format!("Directory no longer exists: {}", path));
+                return;
+            }
+            _ => {}
+        }
+
+        // Nothing matches the search term: create a session in the directory it names
+        if self.selected_item().is_none() && self.search_engine.is_searching() {
+            self.create_from_query();
+            return;
+        }
+
         // Get the selected item data before any mutable borrows
-        let selected_item_data = self.selected_item().map(|item| match item {
-            SessionItem::ExistingSession { name, .. } => (true, name, String::new()),
+        let selected_item_data = self.selected_item().and_then(|item| match item {
+            SessionItem::ExistingSession { name, .. } => Some((true, name, String::new())),
             SessionItem::Directory {
                 session_name, path, ..
-            } => (false, session_name, path),
-            SessionItem::ResurrectableSession { name, .. } => (true, name, String::new()),
+            } => Some((false, session_name, path)),
+            SessionItem::ResurrectableSession { name, .. } => Some((true, name, String::new())),
+            SessionItem::Header { .. } => None,
         });
 
         if let Some((is_session, name, path)) = selected_item_data {
@@ -601,37 +1663,350 @@ impl PluginState {
                 // Switch to existing session
                 self.session_manager
                     .execute_action(SessionAction::Switch(name.clone()));
+                self.feed_switched_session(&name);
                 hide_self();
             } else {
-                // Create new session with incremented name
-                let incremented_name = self
-                    .session_manager
-                    .generate_incremented_name(&name, &self.config.session_separator);
+                self.start_new_session(&name, &path);
+            }
+        }
+    }
 
-                // Set up new session creation
-                self.new_session_info.set_name(&incremented_name);
-                self.new_session_info
-                    .set_folder(Some(std::path::PathBuf::from(&path)));
-                self.new_session_info.advance_to_layout_selection();
-                self.active_screen = ActiveScreen::NewSession;
+    /// Go to the layout step of a new session in a directory, with an incremented name
+    fn start_new_session(&mut self, name: &str, path: &str) {
+        let incremented_name = self
+            .session_manager
+            .generate_incremented_name(name, &self.config.session_separator);
+
+        // Set up new session creation
+        self.new_session_info.set_name(&incremented_name);
+        self.new_session_info
+            .set_folder(Some(std::path::PathBuf::from(path)));
+        self.new_session_info.advance_to_layout_selection();
+        self.active_screen = ActiveScreen::NewSession;
+    }
+
+    /// Create a session from a search term without matches, like `z <terms>`:
+    /// a term that looks like a path is used as the directory, otherwise zoxide picks it
+    fn create_from_query(&mut self) {
+        let query = self.search_engine.search_term().trim().to_string();
+        if query.starts_with('/') || query.starts_with('~') {
+            let path = path_entry::expand_tilde(&query, self.home_dir.as_deref());
+            let path = path.trim_end_matches('/');
+            let path = if path.is_empty() { "/" } else { path };
+            if Self::host_path(std::path::Path::new(path)).is_dir() {
+                self.create_session_in_directory(path);
+            } else {
+                self.set_error(format!("Not a directory: {}", path));
             }
+            return;
+        }
+
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        let command = self.config.zoxide_command.query_terms(&terms);
+        let mut context = BTreeMap::new();
+        context.insert("zsm_query_create".to_string(), query.clone());
+        self.run_zoxide(command, context);
+    }
+
+    /// Start a new session in a directory found from the search term,
+    /// named with the same rules as the listed directories
+    pub fn create_session_in_directory(&mut self, path: &str) {
+        let listed = self
+            .zoxide_directories
+            .iter()
+            .find(|dir| dir.directory == path)
+            .map(|dir| dir.session_name.clone());
+        let name = listed.unwrap_or_else(|| {
+            let mut directories = self.zoxide_directories.clone();
+            directories.push(ZoxideDirectory {
+                directory: path.to_string(),
+                ..Default::default()
+            });
+            self.generate_smart_session_names(&mut directories);
+            directories
+                .pop()
+                .map(|dir| dir.session_name)
+                .unwrap_or_default()
+        });
+        self.start_new_session(&name, path);
+    }
+
+    /// Path and session name of the selected directory, reporting an error for anything else
+    fn selected_directory(&mut self) -> Option<(String, String)> {
+        match self.selected_item() {
+            Some(SessionItem::Directory {
+                path,
+                vanished: true,
+                ..
+            }) => {
+                self.set_error(format!("Directory no longer exists: {}", path));
+                None
+            }
+            Some(SessionItem::Directory {
+                path, session_name, ..
+            }) => Some((path, session_name)),
+            _ => {
+                self.set_error("Select a directory to open it in this session".to_string());
+                None
+            }
+        }
+    }
+
+    /// Open the selected directory as a new tab named after it
+    fn open_directory_in_tab(&mut self) {
+        if let Some((path, name)) = self.selected_directory() {
+            new_tab(Some(name.as_str()), Some(path.as_str()));
+            hide_self();
+        }
+    }
+
+    /// Open the selected directory as a new tiled or floating pane in the current tab
+    fn open_directory_in_pane(&mut self, floating: bool) {
+        if let Some((path, _)) = self.selected_directory() {
+            if floating {
+                open_terminal_floating(&path, None);
+            } else {
+                open_terminal(&path);
+            }
+            hide_self();
+        }
+    }
+
+    /// Pick a layout for the selected directory before opening it as a new tab
+    fn pick_tab_layout(&mut self) {
+        if let Some((path, _)) = self.selected_directory() {
+            self.tab_directory = Some(path);
+            self.new_session_info.advance_to_layout_selection();
+            self.active_screen = ActiveScreen::TabLayout;
+        }
+    }
+
+    /// Leave layout selection for a new tab, back to the main screen
+    fn close_tab_layout(&mut self) {
+        self.tab_directory = None;
+        // Leaves the new session screen in its initial name entry state
+        self.new_session_info.correct_session_name();
+        self.active_screen = ActiveScreen::Main;
+    }
+
+    /// Open the directory being opened as a tab with the selected layout
+    fn open_tab_with_selected_layout(&mut self) {
+        let (Some(path), Some(layout)) = (
+            self.tab_directory.clone(),
+            self.new_session_info.selected_layout_info(),
+        ) else {
+            return;
+        };
+        let kdl = tab_layout::stringified(&layout, |file| {
+            std::fs::read_to_string(Self::host_path(std::path::Path::new(file))).ok()
+        });
+        match kdl.and_then(|kdl| {
+            tab_layout::with_cwd(&kdl, &path)
+                .ok_or_else(|| format!("Layout '{}' has no layout block", layout.name()))
+        }) {
+            Ok(kdl) => {
+                new_tabs_with_layout(&kdl);
+                self.close_tab_layout();
+                hide_self();
+            }
+            Err(err) => self.set_error(err),
         }
     }
 
+    /// Directory being opened as a new tab, while its layout is picked
+    pub fn tab_directory(&self) -> Option<&str> {
+        self.tab_directory.as_deref()
+    }
+
     /// Handle delete key
+    /// Deletes all marked sessions if any are marked, otherwise the selected session
     fn handle_delete_key(&mut self) {
+        if !self.marked_sessions.is_empty() {
+            // Keep the confirmation list in display order rather than alphabetical
+            let mut session_names: Vec<String> = self
+                .combined_items()
+                .iter()
+                .filter_map(Self::session_name_of)
+                .filter(|name| self.marked_sessions.contains(name))
+                .collect();
+            // Include marked sessions that are currently hidden (e.g. dead sessions toggled off)
+            for name in &self.marked_sessions {
+                if !session_names.contains(name) {
+                    session_names.push(name.clone());
+                }
+            }
+            let refused = self.session_manager.start_deletion(session_names);
+            if !refused.is_empty() {
+                self.set_error(format!(
+                    "Protected sessions skipped (delete them individually): {}",
+                    refused.join(", ")
+                ));
+            }
+            return;
+        }
+
         // Get the selected item data before any mutable borrows
-        let selected_session_name = self.selected_item().and_then(|item| match item {
-            SessionItem::ExistingSession { name, .. } => Some(name),
-            SessionItem::ResurrectableSession { name, .. } => Some(name),
-            _ => None,
-        });
+        let selected_session_name = self
+            .selected_item()
+            .and_then(|item| Self::session_name_of(&item));
+
+        if let Some(session_name) = selected_session_name {
+            self.session_manager.start_deletion(vec![session_name]);
+        }
+    }
+
+    /// Toggle deletion protection of the selected session
+    fn toggle_protect_selected(&mut self) {
+        let selected_session_name = self
+            .selected_item()
+            .and_then(|item| Self::session_name_of(&item));
+
+        if let Some(session_name) = selected_session_name {
+            self.session_manager.toggle_protected(&session_name);
+        }
+    }
+
+    /// Get the session name of an item that can be killed or deleted (None for directories)
+    fn session_name_of(item: &SessionItem) -> Option<String> {
+        match item {
+            SessionItem::ExistingSession { name, .. } => Some(name.clone()),
+            SessionItem::ResurrectableSession { name, .. } => Some(name.clone()),
+            SessionItem::Directory { .. } | SessionItem::Header { .. } => None,
+        }
+    }
+
+    /// Expand or collapse the folder (or section) of the selected item and keep its header selected
+    fn toggle_selected_group(&mut self) {
+        if self.search_engine.is_searching() {
+            // Search results are not sectioned
+            return;
+        }
+        match self.selected_item() {
+            Some(SessionItem::Header { group, folder, .. }) => self.toggle_group(group, folder),
+            Some(item) => {
+                let folder = self.folder_of(&item);
+                self.toggle_group(ItemGroup::of(&item), folder);
+            }
+            None => {}
+        }
+    }
+
+    /// Expand or collapse a section or one of its folders, selecting its header
+    fn toggle_group(&mut self, group: ItemGroup, folder: Option<String>) {
+        let entry = (group, folder);
+        if !self.collapsed_groups.remove(&entry) {
+            self.collapsed_groups.insert(entry.clone());
+        }
+        Self::write_collapsed_groups(&self.collapsed_groups);
+
+        let key = ItemKey::Header(entry.0, entry.1);
+        let items = self.display_items();
+        if let Some(index) = items.iter().position(|item| item.has_key(&key)) {
+            self.selection.select(&items, index);
+        }
+    }
+
+    /// Remove the selected directory from the zoxide database if it no longer exists
+    fn prune_selected_directory(&mut self) {
+        match self.selected_item() {
+            Some(SessionItem::Directory {
+                path,
+                vanished: true,
+                ..
+            }) => self.remove_zoxide_directories(vec![path]),
+            Some(SessionItem::Directory { .. }) => {
+                self.set_error("Only directories that no longer exist can be pruned".to_string())
+            }
+            _ => {}
+        }
+    }
+
+    /// Remove every directory that no longer exists from the zoxide database
+    fn prune_vanished_directories(&mut self) {
+        let paths: Vec<String> = self
+            .zoxide_directories
+            .iter()
+            .filter(|dir| dir.vanished)
+            .map(|dir| dir.directory.clone())
+            .collect();
+        if paths.is_empty() {
+            self.set_error("No vanished directories to prune".to_string());
+        } else {
+            self.remove_zoxide_directories(paths);
+        }
+    }
+
+    /// Run `zoxide remove` for directories and drop them from the list right away
+    fn remove_zoxide_directories(&mut self, paths: Vec<String>) {
+        if !self
+            .config
+            .directory_sources
+            .contains(&DirectorySource::Zoxide)
+        {
+            self.set_error("Pruning requires the zoxide directory source".to_string());
+            return;
+        }
+        let mut context = BTreeMap::new();
+        context.insert("zsm_zoxide_remove".to_string(), paths.len().to_string());
+        let command = self.config.zoxide_command.remove(&paths);
+        self.run_zoxide(command, context);
+
+        self.zoxide_directories
+            .retain(|dir| !paths.contains(&dir.directory));
+        self.update_search_if_needed();
+    }
+
+    /// Toggle the batch mark on the selected session
+    fn toggle_mark_selected(&mut self) {
+        let selected_session_name = self
+            .selected_item()
+            .and_then(|item| Self::session_name_of(&item));
 
         if let Some(session_name) = selected_session_name {
-            self.session_manager.start_deletion(session_name);
+            if !self.marked_sessions.remove(&session_name) {
+                self.marked_sessions.insert(session_name);
+            }
+        }
+    }
+
+    /// Mark every visible session (all search matches when searching)
+    /// If all of them are already marked, unmark them instead
+    fn toggle_mark_all_visible(&mut self) {
+        let visible: Vec<String> = self
+            .display_items()
+            .iter()
+            .filter_map(Self::session_name_of)
+            .collect();
+
+        if visible
+            .iter()
+            .all(|name| self.marked_sessions.contains(name))
+        {
+            for name in &visible {
+                self.marked_sessions.remove(name);
+            }
+        } else {
+            self.marked_sessions.extend(visible);
         }
     }
 
+    /// Drop marks for sessions that no longer exist
+    fn prune_marked_sessions(&mut self) {
+        let known: HashSet<String> = self
+            .session_manager
+            .sessions()
+            .iter()
+            .map(|s| s.name.clone())
+            .chain(
+                self.session_manager
+                    .resurrectable_sessions()
+                    .iter()
+                    .map(|(name, _)| name.clone()),
+            )
+            .collect();
+        self.marked_sessions.retain(|name| known.contains(name));
+    }
+
     /// Update search if currently searching
     fn update_search_if_needed(&mut self) {
         if self.search_engine.is_searching() {
@@ -676,6 +2051,119 @@ impl PluginState {
         );
     }
 
+    /// Open the directory browser at the new session folder, or the home directory
+    fn open_browser(&mut self) {
+        let start = self
+            .new_session_info
+            .new_session_folder()
+            .cloned()
+            .or_else(|| self.home_dir.clone())
+            .unwrap_or_else(|| std::path::PathBuf::from("/"));
+        if self.browse(start) {
+            self.active_screen = ActiveScreen::Browse;
+        }
+    }
+
+    /// Where a host path is mapped inside the plugin (the host root is mapped to /host
+    /// once permissions are granted)
+    fn host_path(path: &std::path::Path) -> std::path::PathBuf {
+        std::path::Path::new(crate::HOST_ROOT).join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// Names of the subdirectories of a host directory
+    fn read_subdirectories(directory: &std::path::Path) -> std::io::Result<Vec<String>> {
+        Ok(std::fs::read_dir(Self::host_path(directory))?
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Show a directory in the browser, returning false (with an error) if it can't be read
+    fn browse(&mut self, directory: std::path::PathBuf) -> bool {
+        match Self::read_subdirectories(&directory) {
+            Ok(subdirectories) => {
+                self.browser.open(directory, subdirectories);
+                true
+            }
+            Err(e) => {
+                self.set_error(format!(
+                    "Cannot read directory {}: {}",
+                    directory.display(),
+                    e
+                ));
+                false
+            }
+        }
+    }
+
+    /// Handle key input in the directory browser
+    fn handle_browser_key(&mut self, key: KeyWithModifier) -> bool {
+        const BROWSER_ACTIONS: [Action; 5] = [
+            Action::Confirm,
+            Action::Cancel,
+            Action::Up,
+            Action::Down,
+            Action::ToggleHidden,
+        ];
+
+        match self.config.keymap.action_for(&key, &BROWSER_ACTIONS, false) {
+            Some(Action::Confirm) => {
+                if let Some(path) = self.browser.selected_path() {
+                    self.new_session_info.set_folder(Some(path));
+                    self.active_screen = ActiveScreen::NewSession;
+                }
+                true
+            }
+            Some(Action::Cancel) => {
+                self.active_screen = ActiveScreen::NewSession;
+                true
+            }
+            Some(Action::Up) => {
+                self.browser.move_up();
+                true
+            }
+            Some(Action::Down) => {
+                self.browser.move_down();
+                true
+            }
+            Some(Action::ToggleHidden) => {
+                self.browser.toggle_hidden();
+                true
+            }
+            _ => match key.bare_key {
+                BareKey::Right if key.has_no_modifiers() => {
+                    if let Some(child) = self.browser.child() {
+                        self.browse(child);
+                    }
+                    true
+                }
+                BareKey::Left | BareKey::Backspace
+                    if key.has_no_modifiers() && self.browser.filter().is_empty() =>
+                {
+                    if let Some(parent) = self.browser.parent() {
+                        self.browse(parent);
+                    }
+                    true
+                }
+                _ => self.browser.handle_filter_key(&key),
+            },
+        }
+    }
+
+    /// Directory browser (for UI rendering)
+    pub fn browser(&self) -> &DirectoryBrowser {
+        &self.browser
+    }
+
+    /// Set the home directory of the host user
+    pub fn set_home_dir(&mut self, home: &str) {
+        let home = home.trim();
+        if home.starts_with('/') {
+            self.home_dir = Some(std::path::PathBuf::from(home));
+        }
+    }
+
     /// Check if a request ID is valid (exists in our request list)
     pub fn is_valid_request_id(&self, request_id: &str) -> bool {
         self.request_ids.contains(&request_id.to_string())
@@ -695,32 +2183,36 @@ impl PluginState {
     fn handle_quick_session_creation(&mut self) {
         use zellij_tile::prelude::{switch_session_with_cwd, switch_session_with_layout};
 
-        // Get the selected item data or search term
-        let (session_name, session_folder) = if let Some(selected_item) = self.selected_item() {
-            match selected_item {
-                SessionItem::ExistingSession { name, .. } => {
-                    // Switch to existing session
-                    switch_session_with_cwd(Some(&name), None);
-                    hide_self();
-                    return;
-                }
-                SessionItem::ResurrectableSession { name, .. } => {
-                    switch_session_with_cwd(Some(&name), None);
-                    hide_self();
-                    return;
-                }
-                SessionItem::Directory {
-                    session_name, path, ..
-                } => {
-                    let incremented_name = self
-                        .session_manager
-                        .generate_incremented_name(&session_name, &self.config.session_separator);
-                    (incremented_name, Some(std::path::PathBuf::from(path)))
-                }
+        // Get the selected item data (headers are not selectable targets)
+        let (session_name, session_folder) = match self.selected_item() {
+            Some(SessionItem::ExistingSession { name, .. })
+            | Some(SessionItem::ResurrectableSession { name, .. }) => {
+                // Switch to existing session
+                switch_session_with_cwd(Some(&name), None);
+                self.feed_switched_session(&name);
+                hide_self();
+                return;
+            }
+            Some(SessionItem::Directory {
+                path,
+                vanished: true,
+                ..
+            }) => {
+                self.set_error(format!("Directory no longer exists: {}", path));
+                return;
+            }
+            Some(SessionItem::Directory {
+                session_name, path, ..
+            }) => {
+                let incremented_name = self
+                    .session_manager
+                    .generate_incremented_name(&session_name, &self.config.session_separator);
+                (incremented_name, Some(std::path::PathBuf::from(path)))
+            }
+            Some(SessionItem::Header { .. }) | None => {
+                self.set_error("Please select a directory".to_string());
+                return;
             }
-        } else {
-            self.set_error("Please select a directory".to_string());
-            return;
         };
 
         // Validate session name
@@ -744,6 +2236,14 @@ impl PluginState {
             Self::write_previous_session(current);
         }
 
+        // Remember which directory the session belongs to
+        let created_folder = session_folder
+            .as_ref()
+            .map(|folder| folder.to_string_lossy().to_string());
+        if let Some(ref folder) = created_folder {
+            self.record_session_directory(&session_name, folder);
+        }
+
         // Create session with default layout if configured
         match &self.config.default_layout {
             Some(layout_name) => {
@@ -780,6 +2280,9 @@ impl PluginState {
             }
         }
 
+        if let Some(folder) = created_folder {
+            self.feed_created_session(&folder);
+        }
         hide_self();
     }
 
@@ -792,7 +2295,9 @@ impl PluginState {
             &[
                 "sh",
                 "-c",
-                &format!("echo '{}' > /tmp/zsm-previous-session", session_name),
+                "printf '%s\\n' \"$1\" > /tmp/zsm-previous-session",
+                "sh",
+                session_name,
             ],
             context,
         );
@@ -816,10 +2321,11 @@ impl PluginState {
     /// Set previous session (called from async result)
     pub fn set_previous_session(&mut self, name: Option<String>) {
         self.previous_session_name = name;
-        self.selected_index = None;
+        self.selection.clear();
     }
 
     /// Write MRU timestamp for a session via shell command
+    /// A timestamp of 0 forgets the session's rank
     fn write_mru_timestamp(session_name: &str, timestamp: u64) {
         use zellij_tile::prelude::run_command;
         let mut context = BTreeMap::new();
@@ -828,15 +2334,244 @@ impl PluginState {
             &[
                 "sh",
                 "-c",
-                &format!(
-                    "echo '{}:{}' >> /tmp/zsm-mru-timestamps",
-                    session_name, timestamp
-                ),
+                "printf '%s:%s\\n' \"$1\" \"$2\" >> /tmp/zsm-mru-timestamps",
+                "sh",
+                session_name,
+                &timestamp.to_string(),
             ],
             context,
         );
     }
 
+    /// Record the directory of a session about to be created from the new session screen
+    fn record_new_session_directory(&mut self) {
+        let name = self.new_session_info.name().to_string();
+        if let Some(folder) = self.new_session_info.new_session_folder() {
+            if !name.is_empty() {
+                let folder = folder.to_string_lossy().to_string();
+                self.record_session_directory(&name, &folder);
+            }
+        }
+    }
+
+    /// Feed the folder of a session created from the new session screen back into zoxide
+    fn feed_new_session_directory(&mut self) {
+        if let Some(folder) = self.new_session_info.new_session_folder() {
+            let folder = folder.to_string_lossy().to_string();
+            self.feed_created_session(&folder);
+        }
+    }
+
+    /// Feed the directory of a newly created session back into zoxide (`zoxide_add_on_create`)
+    fn feed_created_session(&mut self, directory: &str) {
+        if self.config.zoxide_add_on_create {
+            self.zoxide_add(directory);
+        }
+    }
+
+    /// Feed the recorded directory of a session switched to back into zoxide (`zoxide_add_on_switch`)
+    fn feed_switched_session(&mut self, session_name: &str) {
+        if !self.config.zoxide_add_on_switch {
+            return;
+        }
+        if let Some(directory) = self
+            .session_manager
+            .session_directory(session_name)
+            .map(str::to_string)
+        {
+            self.zoxide_add(&directory);
+        }
+    }
+
+    /// Run `zoxide add` for a directory and bump its score locally so the list reflects it
+    fn zoxide_add(&mut self, directory: &str) {
+        let mut context = BTreeMap::new();
+        context.insert("zsm_zoxide_add".to_string(), directory.to_string());
+        let command = self.config.zoxide_command.add(directory);
+        self.run_zoxide(command, context);
+
+        if zoxide::bump_ranking(&mut self.zoxide_directories, directory) {
+            self.update_search_if_needed();
+        }
+    }
+
+    /// Run a zoxide command with the configured environment
+    /// The command line is kept in the context for error messages
+    fn run_zoxide(&self, command: Vec<String>, mut context: BTreeMap<String, String>) {
+        use zellij_tile::prelude::run_command_with_env_variables_and_cwd;
+        let env = self.config.zoxide_command.env.clone();
+        context.insert(
+            "zsm_command".to_string(),
+            zoxide::display_command(&command, &env),
+        );
+        let command: Vec<&str> = command.iter().map(String::as_str).collect();
+        run_command_with_env_variables_and_cwd(
+            &command,
+            env,
+            std::path::PathBuf::from("."),
+            context,
+        );
+    }
+
+    /// Record and persist the directory a session belongs to
+    fn record_session_directory(&mut self, session_name: &str, directory: &str) {
+        self.session_manager
+            .set_session_directory(session_name, directory);
+        Self::write_session_directory(session_name, directory);
+    }
+
+    /// Append a session directory association via shell command
+    /// An empty directory forgets the session's association
+    fn write_session_directory(session_name: &str, directory: &str) {
+        use zellij_tile::prelude::run_command;
+        let mut context = BTreeMap::new();
+        context.insert("zsm_internal".to_string(), "directory_write".to_string());
+        run_command(
+            &[
+                "sh",
+                "-c",
+                "printf '%s\\t%s\\n' \"$1\" \"$2\" >> /tmp/zsm-session-directories",
+                "sh",
+                session_name,
+                directory,
+            ],
+            context,
+        );
+    }
+
+    /// Request async read of session directory associations
+    pub fn request_session_directories_read(&self) {
+        use zellij_tile::prelude::run_command;
+        let mut context = BTreeMap::new();
+        context.insert("zsm_read_directories".to_string(), "true".to_string());
+        run_command(
+            &[
+                "sh",
+                "-c",
+                "cat /tmp/zsm-session-directories 2>/dev/null || echo ''",
+            ],
+            context,
+        );
+    }
+
+    /// Parse session directory associations from file data and update session manager
+    pub fn set_session_directories(&mut self, data: &str) {
+        use std::collections::HashMap;
+
+        // Parse lines of format "session_name<TAB>directory"
+        // Later lines override earlier ones (most recent write wins),
+        // an empty directory removes the association (written on rename)
+        let mut directories: HashMap<String, String> = HashMap::new();
+        for (name, directory) in data.lines().filter_map(|line| line.split_once('\t')) {
+            if name.is_empty() {
+                continue;
+            }
+            if directory.is_empty() {
+                directories.remove(name);
+            } else {
+                directories.insert(name.to_string(), directory.to_string());
+            }
+        }
+
+        self.session_manager.set_session_directories(directories);
+        self.request_git_roots();
+        self.update_search_if_needed();
+    }
+
+    /// Persist collapsed sections and folders via shell command
+    /// One entry per line: the section id, followed by a tab and the folder for folders
+    fn write_collapsed_groups(groups: &BTreeSet<(ItemGroup, Option<String>)>) {
+        use zellij_tile::prelude::run_command;
+        let mut context = BTreeMap::new();
+        context.insert("zsm_internal".to_string(), "collapsed_write".to_string());
+        let entries: Vec<String> = groups
+            .iter()
+            .map(|(group, folder)| match folder {
+                Some(folder) => format!("{}\t{}", group.id(), folder),
+                None => group.id().to_string(),
+            })
+            .collect();
+        let mut command = vec![
+            "sh",
+            "-c",
+            "printf '%s\\n' \"$@\" > /tmp/zsm-collapsed-groups",
+            "sh",
+        ];
+        command.extend(entries.iter().map(String::as_str));
+        run_command(&command, context);
+    }
+
+    /// Request async read of collapsed sections
+    pub fn request_collapsed_groups_read(&self) {
+        use zellij_tile::prelude::run_command;
+        let mut context = BTreeMap::new();
+        context.insert("zsm_read_collapsed".to_string(), "true".to_string());
+        run_command(
+            &[
+                "sh",
+                "-c",
+                "cat /tmp/zsm-collapsed-groups 2>/dev/null || echo ''",
+            ],
+            context,
+        );
+    }
+
+    /// Parse collapsed sections and folders from file data
+    pub fn set_collapsed_groups(&mut self, data: &str) {
+        self.collapsed_groups = data
+            .lines()
+            .filter_map(|line| match line.split_once('\t') {
+                Some((id, folder)) => {
+                    ItemGroup::from_id(id).map(|group| (group, Some(folder.to_string())))
+                }
+                None => ItemGroup::from_id(line.trim()).map(|group| (group, None)),
+            })
+            .collect();
+    }
+
+    /// Request the git repository roots of directories not looked up yet
+    /// Only needed when grouping by git root
+    fn request_git_roots(&self) {
+        use zellij_tile::prelude::run_command;
+        if self.config.group_by != GroupBy::GitRoot {
+            return;
+        }
+        let session_directories = self.session_manager.session_directories();
+        let unknown: BTreeSet<&str> = self
+            .zoxide_directories
+            .iter()
+            .map(|dir| dir.directory.as_str())
+            .chain(session_directories.values().map(String::as_str))
+            .filter(|path| !path.is_empty() && !self.git_roots.contains_key(*path))
+            .collect();
+        if unknown.is_empty() {
+            return;
+        }
+
+        let mut context = BTreeMap::new();
+        context.insert("zsm_read_git_roots".to_string(), "true".to_string());
+        // Prints "directory<TAB>root" per directory, with an empty root outside a repository
+        let mut command = vec![
+            "sh",
+            "-c",
+            "for d in \"$@\"; do \
+               printf '%s\\t%s\\n' \"$d\" \"$(git -C \"$d\" rev-parse --show-toplevel 2>/dev/null)\"; \
+             done",
+            "sh",
+        ];
+        command.extend(unknown);
+        run_command(&command, context);
+    }
+
+    /// Parse git repository roots from command output
+    pub fn set_git_roots(&mut self, data: &str) {
+        for (path, root) in data.lines().filter_map(|line| line.split_once('\t')) {
+            let root = (!root.is_empty()).then(|| root.to_string());
+            self.git_roots.insert(path.to_string(), root);
+        }
+        self.update_search_if_needed();
+    }
+
     /// Request async read of MRU timestamps
     pub fn request_mru_timestamps_read(&self) {
         use zellij_tile::prelude::run_command;
@@ -859,7 +2594,8 @@ impl PluginState {
         let mut timestamps: HashMap<String, u64> = HashMap::new();
 
         // Parse lines of format "session_name:timestamp"
-        // Later lines override earlier ones (most recent write wins)
+        // Later lines override earlier ones (most recent write wins),
+        // a timestamp of 0 removes the entry (written on rename)
         for line in data.lines() {
             let line = line.trim();
             if line.is_empty() {
@@ -870,8 +2606,14 @@ impl PluginState {
             if let Some(colon_pos) = line.rfind(':') {
                 let name = &line[..colon_pos];
                 let ts_str = &line[colon_pos + 1..];
-                if let Ok(ts) = ts_str.parse::<u64>() {
-                    timestamps.insert(name.to_string(), ts);
+                match ts_str.parse::<u64>() {
+                    Ok(0) => {
+                        timestamps.remove(name);
+                    }
+                    Ok(ts) => {
+                        timestamps.insert(name.to_string(), ts);
+                    }
+                    Err(_) => {}
                 }
             }
         }
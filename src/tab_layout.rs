@@ -0,0 +1,76 @@
+use std::path::Path;
+use zellij_tile::prelude::LayoutInfo;
+use zellij_utils::input::layout::Layout;
+
+/// KDL of a layout, reading layout files with `read_file`
+pub fn stringified(
+    layout: &LayoutInfo,
+    read_file: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    match layout {
+        LayoutInfo::BuiltIn(name) => Layout::stringified_from_default_assets(Path::new(name))
+            .map(|(_, kdl, _)| kdl)
+            .map_err(|_| format!("Unknown built-in layout '{}'", name)),
+        LayoutInfo::File(path) => {
+            read_file(path).ok_or_else(|| format!("Could not read layout file {}", path))
+        }
+        LayoutInfo::Stringified(kdl) => Ok(kdl.clone()),
+        LayoutInfo::Url(url) => Err(format!(
+            "Layouts from URLs cannot be opened as a tab: {}",
+            url
+        )),
+    }
+}
+
+/// Set the working directory of every pane in a layout by adding a `cwd` node to the
+/// top-level `layout` block (relative pane cwds are resolved against it)
+/// Returns None if the KDL has no `layout` block
+pub fn with_cwd(kdl: &str, cwd: &str) -> Option<String> {
+    let mut offset = 0;
+    for line in kdl.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("layout") {
+            if rest.trim_start().starts_with('{') {
+                let brace = offset + line.find('{')?;
+                let escaped = cwd.replace('\\', "\\\\").replace('"', "\\\"");
+                return Some(format!(
+                    "{}\n    cwd \"{}\"{}",
+                    &kdl[..=brace],
+                    escaped,
+                    &kdl[brace + 1..]
+                ));
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_cwd() {
+        let kdl = "// comment\nlayout {\n    pane\n}\n";
+        assert_eq!(
+            with_cwd(kdl, "/home/u/my \"app\"").as_deref(),
+            Some("// comment\nlayout {\n    cwd \"/home/u/my \\\"app\\\"\"\n    pane\n}\n")
+        );
+        assert_eq!(with_cwd("pane\n", "/tmp"), None);
+    }
+
+    #[test]
+    fn test_stringified_layouts() {
+        let no_files = |_: &str| None;
+        let builtin = stringified(&LayoutInfo::BuiltIn("compact".to_string()), no_files).unwrap();
+        assert!(with_cwd(&builtin, "/tmp").is_some());
+        assert!(stringified(&LayoutInfo::File("/l.kdl".to_string()), no_files).is_err());
+        assert_eq!(
+            stringified(&LayoutInfo::File("/l.kdl".to_string()), |_: &str| Some(
+                "layout {}".to_string()
+            )),
+            Ok("layout {}".to_string())
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::new_session_info::NewSessionInfo;
+use crate::path_entry::PathEntry;
+use crate::ui::theme::Theme;
 use zellij_tile::prelude::*;
 
 #[derive(Copy, Clone, Debug)]
@@ -14,6 +16,215 @@ impl Colors {
     }
 }
 
+/// Screen position of the rows of a rendered list, for mapping mouse clicks to items
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ListGeometry {
+    /// Screen line of the first rendered item
+    pub top: usize,
+    /// List index of the first rendered item
+    pub first_index: usize,
+    /// Number of rendered items
+    pub len: usize,
+}
+
+impl ListGeometry {
+    /// List index of the item rendered on a screen line
+    pub fn index_at(&self, line: usize) -> Option<usize> {
+        (line >= self.top && line < self.top + self.len).then(|| self.first_index + line - self.top)
+    }
+}
+
+/// Single-line text input with a cursor, word editing and horizontal scrolling
+///
+/// Supported editing keys:
+/// - `←`/`→` move by character, `Alt+b`/`Alt+f` move by word, `Home`/`End` jump to the ends
+/// - `Backspace`/`Del` delete around the cursor, `Ctrl+w` deletes the previous word,
+///   `Ctrl+u` deletes everything before the cursor
+#[derive(Debug, Default, Clone)]
+pub struct TextInput {
+    /// Current text
+    text: String,
+    /// Cursor position in characters (0..=char count)
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Get the current text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Check if the input is empty
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Get the cursor position in characters
+    #[allow(dead_code)] // API completeness - useful for debugging/future features
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replace the text and move the cursor to the end
+    pub fn set_text(&mut self, text: &str) {
+        self.clear();
+        self.insert_str(text);
+    }
+
+    /// Clear the text
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Insert text at the cursor, dropping newlines and other control characters
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            let index = self.byte_index(self.cursor);
+            self.text.insert(index, c);
+            self.cursor += 1;
+        }
+    }
+
+    /// Handle an editing key
+    /// Returns true if the key was consumed by the input
+    pub fn handle_key(&mut self, key: &KeyWithModifier) -> bool {
+        let len = self.char_count();
+        match key.bare_key {
+            BareKey::Char(c) if key.has_no_modifiers() => {
+                self.insert_str(&c.to_string());
+            }
+            BareKey::Backspace if key.has_no_modifiers() => {
+                if self.cursor > 0 {
+                    self.delete_range(self.cursor - 1, self.cursor);
+                    self.cursor -= 1;
+                }
+            }
+            BareKey::Delete if key.has_no_modifiers() => {
+                if self.cursor < len {
+                    self.delete_range(self.cursor, self.cursor + 1);
+                }
+            }
+            BareKey::Left if key.has_no_modifiers() => {
+                self.cursor = self.cursor.saturating_sub(1);
+            }
+            BareKey::Right if key.has_no_modifiers() => {
+                self.cursor = (self.cursor + 1).min(len);
+            }
+            BareKey::Home if key.has_no_modifiers() => {
+                self.cursor = 0;
+            }
+            BareKey::End if key.has_no_modifiers() => {
+                self.cursor = len;
+            }
+            BareKey::Char('w') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                let word_start = self.previous_word_boundary();
+                self.delete_range(word_start, self.cursor);
+                self.cursor = word_start;
+            }
+            BareKey::Char('u') if key.has_modifiers(&[KeyModifier::Ctrl]) => {
+                self.delete_range(0, self.cursor);
+                self.cursor = 0;
+            }
+            BareKey::Char('b') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.cursor = self.previous_word_boundary();
+            }
+            BareKey::Char('f') if key.has_modifiers(&[KeyModifier::Alt]) => {
+                self.cursor = self.next_word_boundary();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Number of characters in the text
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Convert a character position into a byte index
+    fn byte_index(&self, char_position: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_position)
+            .map(|(index, _)| index)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Delete the characters in start..end (character positions)
+    fn delete_range(&mut self, start: usize, end: usize) {
+        let (start, end) = (self.byte_index(start), self.byte_index(end));
+        self.text.replace_range(start..end, "");
+    }
+
+    /// Start of the word before the cursor (skipping separators first)
+    fn previous_word_boundary(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position > 0 && !Self::is_word_char(chars[position - 1]) {
+            position -= 1;
+        }
+        while position > 0 && Self::is_word_char(chars[position - 1]) {
+            position -= 1;
+        }
+        position
+    }
+
+    /// End of the word after the cursor (skipping separators first)
+    fn next_word_boundary(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position < chars.len() && !Self::is_word_char(chars[position]) {
+            position += 1;
+        }
+        while position < chars.len() && Self::is_word_char(chars[position]) {
+            position += 1;
+        }
+        position
+    }
+
+    /// Word characters for word-wise movement and deletion
+    /// Path and session name separators count as word boundaries
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric()
+    }
+
+    /// Get the visible part of the text for a field `width` characters wide
+    /// Scrolls horizontally to keep the cursor visible and appends a `_` cursor at the end
+    /// Returns the display string and the cursor position (in characters) within it
+    pub fn display(&self, width: usize) -> (String, usize) {
+        let width = width.max(1);
+        let len = self.char_count();
+        // One extra column for the end-of-text cursor
+        let start = if len < width {
+            0
+        } else {
+            (self.cursor + 1).saturating_sub(width)
+        };
+
+        let mut display: String = self.text.chars().skip(start).take(width).collect();
+        if self.cursor == len {
+            display.push('_');
+        }
+        (display, self.cursor - start)
+    }
+
+    /// Render the input between a prefix and a suffix, highlighting the cursor position
+    /// The input is scrolled so the whole line fits in `width`
+    pub fn render(&self, prefix: &str, suffix: &str, width: usize, theme: &Option<Theme>) -> Text {
+        let prefix_len = prefix.chars().count();
+        let input_width = width.saturating_sub(prefix_len + suffix.chars().count());
+        let (display, cursor) = self.display(input_width);
+        let line = format!("{}{}{}", prefix, display, suffix);
+        let text = match theme {
+            Some(theme) => theme.content(&line),
+            None => Text::new(&line),
+        };
+        text.color_range(3, prefix_len + cursor..prefix_len + cursor + 1)
+    }
+}
+
+/// Render the new session block, returning the geometry of the layout list if shown
 pub fn render_new_session_block(
     new_session_info: &NewSessionInfo,
     colors: Colors,
@@ -21,39 +232,41 @@ pub fn render_new_session_block(
     max_cols_of_new_session_block: usize,
     x: usize,
     y: usize,
-) {
+) -> Option<ListGeometry> {
+    let mut layout_list_geometry = None;
     let _enter = colors.shortcuts("<ENTER>");
     if new_session_info.entering_new_session_name() {
         let prompt = "New session name:";
         let long_instruction = "when done, blank for random";
-        let new_session_name = new_session_info.name();
+        let name_start = prompt.len() + 1;
         if max_cols_of_new_session_block > 70 {
-            let session_name_text = Text::new(format!(
-                "{} {}_ (<ENTER> {})",
-                prompt, new_session_name, long_instruction
-            ))
-            .color_range(3, ..prompt.len())
-            .color_range(
-                0,
-                prompt.len() + 1..prompt.len() + 1 + new_session_name.len(),
-            )
-            .color_range(
-                3,
-                prompt.len() + new_session_name.len() + 4
-                    ..prompt.len() + new_session_name.len() + 11,
-            );
+            let suffix = format!(" (<ENTER> {})", long_instruction);
+            let (name, cursor) = new_session_info
+                .name_input()
+                .display(max_cols_of_new_session_block.saturating_sub(name_start + suffix.len()));
+            let name_len = name.chars().count();
+            let session_name_text = Text::new(format!("{} {}{}", prompt, name, suffix))
+                .color_range(3, ..prompt.len())
+                .color_range(0, name_start..name_start + name_len)
+                .color_range(3, name_start + cursor..name_start + cursor + 1)
+                .color_range(3, name_start + name_len + 2..name_start + name_len + 9);
             print_text_with_coordinates(session_name_text, x, y + 1, None, None);
         } else {
-            let session_name_text = Text::new(format!("{} {}_ <ENTER>", prompt, new_session_name))
+            let suffix = " <ENTER>";
+            let (name, cursor) = new_session_info
+                .name_input()
+                .display(max_cols_of_new_session_block.saturating_sub(name_start + suffix.len()));
+            let name_len = name.chars().count();
+            let session_name_text = Text::new(format!("{} {}{}", prompt, name, suffix))
                 .color_range(3, ..prompt.len())
-                .color_range(
-                    0,
-                    prompt.len() + 1..prompt.len() + 1 + new_session_name.len(),
-                )
-                .color_range(3, prompt.len() + new_session_name.len() + 3..);
+                .color_range(0, name_start..name_start + name_len)
+                .color_range(3, name_start + cursor..name_start + cursor + 1)
+                .color_range(3, name_start + name_len + 1..);
             print_text_with_coordinates(session_name_text, x, y + 1, None, None);
         }
-    } else if new_session_info.entering_layout_search_term() {
+    } else if new_session_info.entering_layout_search_term()
+        && new_session_info.folder_entry().is_none()
+    {
         let new_session_name = if new_session_info.name().is_empty() {
             "<RANDOM>"
         } else {
@@ -75,13 +288,14 @@ pub fn render_new_session_block(
         );
         print_text_with_coordinates(session_name_text, x, y + 1, None, None);
 
-        render_layout_selection_list(
+        layout_list_geometry = Some(render_layout_selection_list(
             new_session_info,
+            "New session layout:",
             max_rows_of_new_session_block.saturating_sub(8),
             max_cols_of_new_session_block,
             x,
             y + 1,
-        );
+        ));
     }
     render_new_session_folder_prompt(
         new_session_info,
@@ -90,35 +304,34 @@ pub fn render_new_session_block(
         (y + max_rows_of_new_session_block).saturating_sub(3),
         max_cols_of_new_session_block,
     );
+    layout_list_geometry
 }
 
+/// Render the layout list under a prompt, returning where its rows were drawn
 pub fn render_layout_selection_list(
     new_session_info: &NewSessionInfo,
+    prompt: &str,
     max_rows_of_new_session_block: usize,
     max_cols_of_new_session_block: usize,
     x: usize,
     y: usize,
-) {
+) -> ListGeometry {
     let layout_search_term = new_session_info.layout_search_term();
-    let layout_indication_line = if max_cols_of_new_session_block > 73 {
+    let term_start = prompt.len() + 1;
+    let term_end = term_start + layout_search_term.len();
+    let layout_indication_line = if max_cols_of_new_session_block > term_start + 53 {
         Text::new(format!(
-            "New session layout: {}_ (Search and select from list, <ENTER> when done)",
-            layout_search_term
+            "{} {}_ (Search and select from list, <ENTER> when done)",
+            prompt, layout_search_term
         ))
-        .color_range(2, ..20)
-        .color_range(1, 20..20 + layout_search_term.len())
-        .color_range(
-            3,
-            52 + layout_search_term.len()..59 + layout_search_term.len(),
-        )
+        .color_range(2, ..term_start)
+        .color_range(1, term_start..term_end)
+        .color_range(3, term_end + 32..term_end + 39)
     } else {
-        Text::new(format!(
-            "New session layout: {}_ <ENTER>",
-            layout_search_term
-        ))
-        .color_range(2, ..20)
-        .color_range(1, 20..20 + layout_search_term.len())
-        .color_range(3, 22 + layout_search_term.len()..)
+        Text::new(format!("{} {}_ <ENTER>", prompt, layout_search_term))
+            .color_range(2, ..term_start)
+            .color_range(1, term_start..term_end)
+            .color_range(3, term_end + 2..)
     };
     print_text_with_coordinates(layout_indication_line, x, y + 1, None, None);
 
@@ -156,6 +369,14 @@ pub fn render_layout_selection_list(
         Some(max_cols_of_new_session_block),
         Some(max_rows_of_new_session_block),
     );
+
+    let (first_index, last_index) =
+        new_session_info.layout_render_range(max_rows_of_new_session_block);
+    ListGeometry {
+        top: y + 3,
+        first_index,
+        len: last_index.saturating_sub(first_index),
+    }
 }
 
 pub fn render_new_session_folder_prompt(
@@ -165,13 +386,17 @@ pub fn render_new_session_folder_prompt(
     y: usize,
     max_cols: usize,
 ) {
+    if let Some(entry) = new_session_info.folder_entry() {
+        render_folder_entry(entry, x, y, max_cols);
+        return;
+    }
     match &new_session_info.new_session_folder {
         Some(folder) => {
             let short_folder_prompt = "New session folder:";
             let folder_path = folder.to_string_lossy();
-            if max_cols > short_folder_prompt.len() + folder_path.len() + 40 {
+            if max_cols > short_folder_prompt.len() + folder_path.len() + 60 {
                 let folder_text = Text::new(format!(
-                    "{} {} (Ctrl+<f> to change, Ctrl+<c> to clear)",
+                    "{} {} (Ctrl+<f> to change, Ctrl+<c> to clear, Ctrl+<e> to edit)",
                     short_folder_prompt, folder_path
                 ))
                 .color_range(2, ..short_folder_prompt.len())
@@ -189,6 +414,11 @@ pub fn render_new_session_folder_prompt(
                     3,
                     short_folder_prompt.len() + folder_path.len() + 23
                         ..short_folder_prompt.len() + folder_path.len() + 31,
+                )
+                .color_range(
+                    3,
+                    short_folder_prompt.len() + folder_path.len() + 42
+                        ..short_folder_prompt.len() + folder_path.len() + 50,
                 );
                 print_text_with_coordinates(folder_text, x, y + 1, None, None);
             } else {
@@ -206,10 +436,159 @@ pub fn render_new_session_folder_prompt(
         }
         None => {
             let folder_prompt = "New session folder (optional):";
-            let folder_text = Text::new(format!("{} Ctrl+<f> to select", folder_prompt))
-                .color_range(2, ..folder_prompt.len())
-                .color_range(3, folder_prompt.len() + 1..folder_prompt.len() + 9);
+            let folder_text = Text::new(format!(
+                "{} Ctrl+<f> to select, Ctrl+<d> to browse, Ctrl+<e> to type",
+                folder_prompt
+            ))
+            .color_range(2, ..folder_prompt.len())
+            .color_range(3, folder_prompt.len() + 1..folder_prompt.len() + 9)
+            .color_range(3, folder_prompt.len() + 21..folder_prompt.len() + 29)
+            .color_range(3, folder_prompt.len() + 41..folder_prompt.len() + 49);
             print_text_with_coordinates(folder_text, x, y + 1, None, None);
         }
     }
 }
+
+/// Render the folder path being typed, with its suggestions listed above it
+fn render_folder_entry(entry: &PathEntry, x: usize, y: usize, max_cols: usize) {
+    let prompt = "New session folder: ";
+    let suffix = " (<Tab> complete, <Enter> set, <Esc> cancel)";
+    let suffix = if max_cols > prompt.len() + suffix.len() + 20 {
+        suffix
+    } else {
+        ""
+    };
+    let input_text = entry
+        .input()
+        .render(prompt, suffix, max_cols, &None)
+        .color_range(2, ..prompt.len() - 1);
+    print_text_with_coordinates(input_text, x, y + 1, None, None);
+
+    // Suggestions fill the rows above the prompt, closest match nearest to it
+    let suggestions = entry.suggestions();
+    let rows = suggestions.len().min(y.saturating_sub(2));
+    for (i, suggestion) in suggestions.iter().take(rows).enumerate() {
+        let mut text = Text::new(format!("  {}", suggestion));
+        if entry.selected() == Some(i) {
+            text = text.selected();
+        }
+        print_text_with_coordinates(text, x, y - i, Some(max_cols), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(bare_key: BareKey) -> KeyWithModifier {
+        KeyWithModifier::new(bare_key)
+    }
+
+    fn input(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        input.set_text(text);
+        input
+    }
+
+    #[test]
+    fn test_insert_at_cursor() {
+        let mut input = input("hllo");
+        input.handle_key(&key(BareKey::Home));
+        input.handle_key(&key(BareKey::Right));
+        input.handle_key(&key(BareKey::Char('e')));
+
+        assert_eq!(input.text(), "hello");
+        assert_eq!(input.cursor(), 2);
+    }
+
+    #[test]
+    fn test_backspace_and_delete_around_cursor() {
+        let mut input = input("abcd");
+        input.handle_key(&key(BareKey::Left));
+        input.handle_key(&key(BareKey::Left));
+        input.handle_key(&key(BareKey::Backspace));
+        input.handle_key(&key(BareKey::Delete));
+
+        assert_eq!(input.text(), "ad");
+        assert_eq!(input.cursor(), 1);
+    }
+
+    #[test]
+    fn test_ctrl_w_deletes_previous_word() {
+        let mut input = input("~/projects/my-app");
+        input.handle_key(&key(BareKey::Char('w')).with_ctrl_modifier());
+        assert_eq!(input.text(), "~/projects/my-");
+
+        input.handle_key(&key(BareKey::Char('w')).with_ctrl_modifier());
+        assert_eq!(input.text(), "~/projects/");
+    }
+
+    #[test]
+    fn test_ctrl_u_deletes_to_start() {
+        let mut input = input("hello world");
+        input.handle_key(&key(BareKey::Left));
+        input.handle_key(&key(BareKey::Char('u')).with_ctrl_modifier());
+
+        assert_eq!(input.text(), "d");
+        assert_eq!(input.cursor(), 0);
+    }
+
+    #[test]
+    fn test_alt_b_and_alt_f_move_by_word() {
+        let mut input = input("one two three");
+        input.handle_key(&key(BareKey::Char('b')).with_alt_modifier());
+        assert_eq!(input.cursor(), 8);
+        input.handle_key(&key(BareKey::Char('b')).with_alt_modifier());
+        assert_eq!(input.cursor(), 4);
+        input.handle_key(&key(BareKey::Char('f')).with_alt_modifier());
+        assert_eq!(input.cursor(), 7);
+    }
+
+    #[test]
+    fn test_pasted_text_arrives_as_character_keys() {
+        let mut input = TextInput::default();
+        for c in "web api".chars() {
+            assert!(input.handle_key(&key(BareKey::Char(c))));
+        }
+        // A pasted newline is an Enter key, left to the screen (which submits)
+        assert!(!input.handle_key(&key(BareKey::Enter)));
+
+        assert_eq!(input.text(), "web api");
+    }
+
+    #[test]
+    fn test_unhandled_keys_are_not_consumed() {
+        let mut input = input("abc");
+        assert!(!input.handle_key(&key(BareKey::Enter)));
+        assert!(!input.handle_key(&key(BareKey::Char('r')).with_ctrl_modifier()));
+        assert_eq!(input.text(), "abc");
+    }
+
+    #[test]
+    fn test_display_scrolls_to_keep_cursor_visible() {
+        let mut input = input("abcdefghij");
+
+        // Cursor at end: last chars plus the end cursor
+        assert_eq!(input.display(5), ("ghij_".to_string(), 4));
+
+        // Cursor at start: window starts at the beginning
+        input.handle_key(&key(BareKey::Home));
+        assert_eq!(input.display(5), ("abcde".to_string(), 0));
+
+        // Short text is shown whole
+        assert_eq!(TextInput::default().display(5), ("_".to_string(), 0));
+    }
+
+    #[test]
+    fn test_list_geometry_maps_lines_to_indices() {
+        let geometry = ListGeometry {
+            top: 3,
+            first_index: 10,
+            len: 4,
+        };
+        assert_eq!(geometry.index_at(2), None);
+        assert_eq!(geometry.index_at(3), Some(10));
+        assert_eq!(geometry.index_at(6), Some(13));
+        assert_eq!(geometry.index_at(7), None);
+    }
+}
@@ -2,6 +2,6 @@ pub mod components;
 pub mod renderer;
 pub mod theme;
 
-pub use components::Colors;
+pub use components::{Colors, ListGeometry, TextInput};
 pub use renderer::PluginRenderer;
 pub use theme::Theme;
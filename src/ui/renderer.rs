@@ -2,13 +2,29 @@ use zellij_tile::prelude::{
     print_table_with_coordinates, print_text_with_coordinates, Palette, Table, Text,
 };
 
+use crate::keybindings::Action;
+use crate::session::manager::UndoableDeletion;
 use crate::session::SessionItem;
-use crate::state::{ActiveScreen, PluginState};
-use crate::ui::{Colors, Theme};
+use crate::state::{ActiveScreen, PluginState, RenameTarget};
+use crate::ui::{Colors, ListGeometry, Theme};
 
 /// Main renderer for the plugin UI
 pub struct PluginRenderer;
 
+/// Width of the quick-select number gutter, including the column gap
+const GUTTER_WIDTH: usize = 2;
+
+/// Per-item state that changes how a session item is rendered
+#[derive(Debug, Clone, Copy, Default)]
+struct ItemFlags {
+    /// Marked for a batch operation
+    marked: bool,
+    /// Protected from deletion
+    protected: bool,
+    /// Nested under a folder header (grouped view)
+    nested: bool,
+}
+
 impl PluginRenderer {
     /// Render the main plugin interface
     pub fn render(state: &mut PluginState, rows: usize, cols: usize) {
@@ -19,18 +35,38 @@ impl PluginRenderer {
                 Self::render_main_screen(state, x, y, width, height);
             }
             ActiveScreen::NewSession => {
-                Self::render_new_session_screen(&*state, x, y, width, height);
+                Self::render_new_session_screen(state, x, y, width, height);
             }
             ActiveScreen::Rename => {
                 Self::render_rename_screen(&*state, x, y, width, height);
             }
+            ActiveScreen::CleanupPreview => {
+                Self::render_cleanup_preview_screen(&*state, x, y, width, height);
+            }
+            ActiveScreen::Diagnostics => {
+                Self::render_diagnostics_screen(&*state, x, y, width, height);
+            }
+            ActiveScreen::Browse => {
+                Self::render_browser_screen(&*state, x, y, width, height);
+            }
+            ActiveScreen::TabLayout => {
+                Self::render_tab_layout_screen(state, x, y, width, height);
+            }
         }
 
         // Render overlays
         if let Some(error) = state.error() {
             Self::render_error(error, x, y, width, height);
-        } else if let Some(session_name) = state.session_manager().pending_deletion() {
-            Self::render_deletion_confirmation(session_name, x, y, width, height, state.colors());
+        } else if !state.session_manager().pending_deletion().is_empty() {
+            Self::render_deletion_confirmation(
+                state.session_manager().pending_deletion(),
+                state,
+                x,
+                y,
+                width,
+                height,
+                state.colors(),
+            );
         }
     }
 
@@ -45,25 +81,79 @@ impl PluginRenderer {
         let theme = state.colors().map(Theme::new);
 
         // Render search indication
-        let search_term = state.search_engine().search_term();
-        let search_text = format!("Search: {}_", search_term);
-        let search_indication = if let Some(theme) = &theme {
-            theme.content(&search_text).color_range(2, ..7)
-        } else {
-            Text::new(&search_text).color_range(1, ..7)
-        };
+        let mut search_suffix = String::new();
+        if state.marked_count() > 0 {
+            search_suffix.push_str(&format!("  ({} marked)", state.marked_count()));
+        }
+        if !state.cleanup_preview().is_empty() {
+            search_suffix.push_str(&format!("  ({} to clean up", state.cleanup_preview().len()));
+            if let Some(hint) = Self::key_hint(state, Action::Cleanup, state.is_normal_mode()) {
+                search_suffix.push_str(&format!(", {} review", hint));
+            }
+            search_suffix.push(')');
+        }
+        // Position indicator (e.g. "12/340") right-aligned on the search bar row
+        let display_items = state.display_items();
+        let items_len = display_items.len();
+        let position = Self::position_indicator(&display_items, state.selected_index());
+        let position_len = position.chars().count();
+        let search_indication = state
+            .search_input()
+            .render(
+                "Search: ",
+                &search_suffix,
+                width.saturating_sub(position_len + 1),
+                &theme,
+            )
+            .color_range(if theme.is_some() { 2 } else { 1 }, ..7);
         print_text_with_coordinates(search_indication, x, y, None, None);
+        print_text_with_coordinates(
+            Text::new(&position).color_range(0, ..),
+            x + width.saturating_sub(position_len),
+            y,
+            None,
+            None,
+        );
+
+        // Render undo notice in the empty row below the search bar
+        if let Some(deletion) = state.session_manager().undoable_deletion() {
+            Self::render_undo_notice(state, deletion, x, y + 1);
+        }
 
         // Render main content
         // Reserve 5 rows: 1 search bar + 1 empty + table + 2 help rows
         let table_rows = height.saturating_sub(5);
-        let table = if state.search_engine().is_searching() {
-            Self::render_search_results(&*state, table_rows, width, &theme)
+        // Leave the last column for the scrollbar when the list doesn't fit
+        let has_scrollbar = items_len + 1 > table_rows;
+        let table_width = if has_scrollbar {
+            width.saturating_sub(1)
         } else {
-            Self::render_all_items(state, table_rows, width, &theme)
+            width
         };
-
-        if state.display_items().is_empty() && !state.search_engine().is_searching() {
+        let (table, (first_row, last_row)) = if state.search_engine().is_searching() {
+            Self::render_search_results(&*state, table_rows, table_width, &theme)
+        } else {
+            Self::render_all_items(state, table_rows, table_width, &theme)
+        };
+        // Item rows start below the table title row
+        state.set_list_geometry(Some(ListGeometry {
+            top: y + 3,
+            first_index: first_row,
+            len: last_row.saturating_sub(first_row),
+        }));
+
+        if state.display_items().is_empty()
+            && !state.search_engine().is_searching()
+            && !state.directories_loaded()
+        {
+            let loading_text = if let Some(theme) = &theme {
+                theme.content("Loading directories…")
+            } else {
+                Text::new("Loading directories…")
+            };
+            print_text_with_coordinates(loading_text, x, y + 2, None, None);
+            state.set_list_geometry(None);
+        } else if state.display_items().is_empty() && !state.search_engine().is_searching() {
             let no_dirs_text = if let Some(theme) = &theme {
                 theme.warning("No zoxide directories found. Make sure zoxide is installed and you have visited some directories.")
             } else {
@@ -71,8 +161,18 @@ impl PluginRenderer {
                     .color_range(1, ..)
             };
             print_text_with_coordinates(no_dirs_text, x, y + 2, None, None);
+            state.set_list_geometry(None);
         } else {
-            print_table_with_coordinates(table, x, y + 2, Some(width), Some(table_rows));
+            print_table_with_coordinates(table, x, y + 2, Some(table_width), Some(table_rows));
+            if has_scrollbar {
+                Self::render_scrollbar(
+                    x + width.saturating_sub(1),
+                    y + 3,
+                    last_row.saturating_sub(first_row),
+                    first_row,
+                    items_len,
+                );
+            }
         }
 
         // Render help text (2 rows starting at y + height - 2)
@@ -81,7 +181,7 @@ impl PluginRenderer {
 
     /// Render new session creation screen
     fn render_new_session_screen(
-        state: &PluginState,
+        state: &mut PluginState,
         x: usize,
         y: usize,
         width: usize,
@@ -91,7 +191,7 @@ impl PluginRenderer {
             .colors()
             .map(Colors::new)
             .unwrap_or_else(|| Colors::new(Palette::default()));
-        crate::ui::components::render_new_session_block(
+        let layout_list_geometry = crate::ui::components::render_new_session_block(
             state.new_session_info(),
             colors,
             height.saturating_sub(2),
@@ -99,54 +199,262 @@ impl PluginRenderer {
             x,
             y,
         );
+        state.set_layout_list_geometry(layout_list_geometry);
     }
 
-    /// Render session rename screen
-    fn render_rename_screen(
-        state: &PluginState,
+    /// Render layout selection for a directory opened as a new tab
+    fn render_tab_layout_screen(
+        state: &mut PluginState,
         x: usize,
         y: usize,
-        _width: usize,
-        _height: usize,
+        width: usize,
+        height: usize,
     ) {
         let theme = state.colors().map(Theme::new);
 
+        let prompt = format!("Open {} in a new tab", state.tab_directory().unwrap_or(""));
+        let prompt_text = if let Some(theme) = &theme {
+            theme.content(&prompt).color_range(2, ..)
+        } else {
+            Text::new(&prompt).color_range(2, ..)
+        };
+        print_text_with_coordinates(prompt_text, x, y + 1, None, None);
+
+        // Reserve rows for the prompt above and the help below
+        let layout_list_geometry = crate::ui::components::render_layout_selection_list(
+            state.new_session_info(),
+            "Tab layout:",
+            height.saturating_sub(6),
+            width,
+            x,
+            y + 1,
+        );
+        state.set_layout_list_geometry(Some(layout_list_geometry));
+
+        let help_text = Self::help_row(&[
+            (Self::key_hint(state, Action::Confirm, false), "Open"),
+            (Self::key_hint(state, Action::Cancel, false), "Cancel"),
+        ]);
+        print_text_with_coordinates(help_text, x, y + height.saturating_sub(1), None, None);
+    }
+
+    /// Render session rename screen
+    fn render_rename_screen(state: &PluginState, x: usize, y: usize, width: usize, _height: usize) {
+        let theme = state.colors().map(Theme::new);
+
         // Render prompt
-        let prompt = "Rename session:";
+        let prompt = match state.rename_target() {
+            Some(RenameTarget::Current(name)) => format!("Rename current session '{}':", name),
+            Some(RenameTarget::Live(name)) => format!("Rename session '{}':", name),
+            Some(RenameTarget::Resurrectable(name)) => {
+                format!("Rename resurrectable session '{}':", name)
+            }
+            None => "Rename session:".to_string(),
+        };
         let prompt_text = if let Some(theme) = &theme {
-            theme.content(prompt).color_range(2, ..)
+            theme.content(&prompt).color_range(2, ..)
         } else {
-            Text::new(prompt).color_range(2, ..)
+            Text::new(&prompt).color_range(2, ..)
         };
         print_text_with_coordinates(prompt_text, x, y, None, None);
 
         // Render current input with cursor
-        let input_display = format!("{}_", state.rename_buffer());
-        let input_text = if let Some(theme) = &theme {
-            theme.content(&input_display)
-        } else {
-            Text::new(&input_display)
-        };
+        let input_text = state.rename_input().render("", "", width, &theme);
         print_text_with_coordinates(input_text, x, y + 2, None, None);
 
         // Render help text
-        let help = "Enter: Confirm • Esc: Cancel";
-        let help_text = if let Some(theme) = &theme {
-            theme.content(help).color_range(1, ..)
+        let help_text = Self::help_row(&[
+            (Self::key_hint(state, Action::Confirm, false), "Confirm"),
+            (Self::key_hint(state, Action::Cancel, false), "Cancel"),
+        ]);
+        print_text_with_coordinates(help_text, x, y + 4, None, None);
+    }
+
+    /// Render preview of resurrectable sessions the cleanup policy would delete
+    fn render_cleanup_preview_screen(
+        state: &PluginState,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) {
+        let theme = state.colors().map(Theme::new);
+
+        let candidates = state.cleanup_preview();
+        let prompt = format!(
+            "Cleanup policy would delete {} resurrectable session(s):",
+            candidates.len()
+        );
+        let prompt_text = if let Some(theme) = &theme {
+            theme.content(&prompt).color_range(2, ..)
         } else {
-            Text::new(help).color_range(1, ..)
+            Text::new(&prompt).color_range(2, ..)
         };
-        print_text_with_coordinates(help_text, x, y + 4, None, None);
+        print_text_with_coordinates(prompt_text, x, y, None, None);
+
+        // Reserve 4 rows: prompt + empty + empty + help
+        let list_rows = height.saturating_sub(4);
+        let mut table = Table::new().add_row(vec!["Session", "Age"]);
+        for (i, name) in candidates.iter().enumerate() {
+            if i >= list_rows.saturating_sub(1) {
+                break;
+            }
+            let age = state
+                .session_manager()
+                .resurrectable_sessions()
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, duration)| format!("{} ago", humantime::format_duration(*duration)))
+                .unwrap_or_default();
+            table = table.add_styled_row(vec![
+                Text::new(format!("↺ {}", name)).color_range(3, 2..),
+                Text::new(age),
+            ]);
+        }
+        print_table_with_coordinates(table, x, y + 2, Some(width), Some(list_rows));
+
+        let help_text = Self::help_row(&[
+            (Self::key_hint(state, Action::Confirm, false), "Delete all"),
+            (Self::key_hint(state, Action::Cancel, false), "Cancel"),
+        ]);
+        print_text_with_coordinates(help_text, x, y + height.saturating_sub(1), None, None);
     }
 
-    /// Render search results table
+    /// Render what was read from each directory source, listing the lines that were skipped
+    fn render_diagnostics_screen(
+        state: &PluginState,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) {
+        let theme = state.colors().map(Theme::new);
+
+        let prompt = format!(
+            "Directory sources ({} line(s) skipped):",
+            state.skipped_line_count()
+        );
+        let prompt_text = if let Some(theme) = &theme {
+            theme.content(&prompt).color_range(2, ..)
+        } else {
+            Text::new(&prompt).color_range(2, ..)
+        };
+        print_text_with_coordinates(prompt_text, x, y, None, None);
+
+        // Reserve 4 rows: prompt + empty + empty + help
+        let list_rows = height.saturating_sub(4);
+        let mut lines: Vec<Text> = Vec::new();
+        for diagnostics in state.source_diagnostics() {
+            let summary = format!(
+                "{}: {} directories, {} line(s) skipped",
+                diagnostics.name,
+                diagnostics.directories,
+                diagnostics.skipped.len()
+            );
+            lines.push(Text::new(&summary).color_range(3, ..diagnostics.name.len()));
+            lines.push(Text::new(format!("  $ {}", diagnostics.command)).color_range(0, ..));
+            if let Some(error) = &diagnostics.error {
+                lines.push(Text::new(format!("  failed: {}", error)).color_range(1, ..9));
+            }
+            for skipped in &diagnostics.skipped {
+                let prefix = format!("  line {}: {}: ", skipped.line, skipped.reason);
+                let prefix_len = prefix.chars().count();
+                lines.push(
+                    Text::new(format!("{}{}", prefix, skipped.content))
+                        .color_range(1, ..prefix_len),
+                );
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Text::new("No directory source has been read yet"));
+        }
+
+        let mut table = Table::new().add_row(vec!["Source"]);
+        for line in lines.into_iter().take(list_rows.saturating_sub(1)) {
+            table = table.add_styled_row(vec![line]);
+        }
+        print_table_with_coordinates(table, x, y + 2, Some(width), Some(list_rows));
+
+        let help_text = Self::help_row(&[(Self::key_hint(state, Action::Cancel, false), "Close")]);
+        print_text_with_coordinates(help_text, x, y + height.saturating_sub(1), None, None);
+    }
+
+    /// Render the directory browser for the folder of a new session
+    fn render_browser_screen(state: &PluginState, x: usize, y: usize, width: usize, height: usize) {
+        let theme = state.colors().map(Theme::new);
+        let browser = state.browser();
+
+        let prompt = format!("Choose folder: {}", browser.directory().display());
+        let prompt_text = if let Some(theme) = &theme {
+            theme.content(&prompt).color_range(2, ..14)
+        } else {
+            Text::new(&prompt).color_range(2, ..14)
+        };
+        print_text_with_coordinates(prompt_text, x, y, None, None);
+
+        let filter_text = browser
+            .filter()
+            .render("Filter: ", "", width, &theme)
+            .color_range(if theme.is_some() { 2 } else { 1 }, ..7);
+        print_text_with_coordinates(filter_text, x, y + 1, None, None);
+
+        // Reserve 5 rows: prompt + filter + empty + table title + help
+        let table_rows = height.saturating_sub(5);
+        let entries = browser.entries();
+        let selected_index = browser.selected_index();
+        let (first_row, last_row) =
+            Self::calculate_render_range(table_rows, entries.len(), selected_index);
+        let mut table = Table::new().add_row(vec!["Directory"]);
+        for (i, (name, indices)) in entries
+            .iter()
+            .enumerate()
+            .take(last_row.min(entries.len()))
+            .skip(first_row)
+        {
+            let label = if *name == crate::browser::CURRENT_DIRECTORY {
+                "./ (this folder)".to_string()
+            } else {
+                format!("{}/", name)
+            };
+            let mut cell = Text::new(&label);
+            if !indices.is_empty() {
+                cell = match &theme {
+                    Some(theme) => theme.highlight(cell, indices.clone()),
+                    None => cell.color_indices(3, indices.clone()),
+                };
+            }
+            if Some(i) == selected_index {
+                cell = cell.selected();
+            }
+            table = table.add_styled_row(vec![cell]);
+        }
+        print_table_with_coordinates(table, x, y + 3, Some(width), Some(table_rows + 1));
+
+        let hidden_label = if browser.show_hidden() {
+            "Hide hidden"
+        } else {
+            "Show hidden"
+        };
+        let help_text = Self::help_row(&[
+            (Some("<←/→>".to_string()), "Up/Into"),
+            (Self::key_hint(state, Action::Confirm, false), "Choose"),
+            (
+                Self::key_hint(state, Action::ToggleHidden, false),
+                hidden_label,
+            ),
+            (Self::key_hint(state, Action::Cancel, false), "Cancel"),
+        ]);
+        print_text_with_coordinates(help_text, x, y + height.saturating_sub(1), None, None);
+    }
+
+    /// Render search results table, returning it with the range of rendered results
     fn render_search_results(
         state: &PluginState,
         table_rows: usize,
         table_width: usize,
         theme: &Option<Theme>,
-    ) -> Table {
-        let mut table = Table::new().add_row(vec!["Directory/Session"]);
+    ) -> (Table, (usize, usize)) {
+        let mut table = Table::new().add_row(vec![" ", "Directory/Session"]);
         let results = state.search_engine().results();
         let selected_index = state.search_engine().selected_index();
 
@@ -156,17 +464,22 @@ impl PluginRenderer {
 
         let (first_row, last_row) =
             Self::calculate_render_range(table_rows, results.len(), selected_index);
+        let last_row = last_row.min(results.len());
 
         for i in first_row..last_row {
             if let Some(result) = results.get(i) {
                 let is_selected = Some(i) == selected_index;
-                let mut table_cells = vec![Self::render_search_result_item(
-                    &result.item,
-                    &result.indices,
-                    table_width.saturating_sub(4),
-                    name_col_width,
-                    theme,
-                )];
+                let mut table_cells = vec![
+                    Self::gutter_cell(i - first_row),
+                    Self::render_search_result_item(
+                        &result.item,
+                        &result.indices,
+                        table_width.saturating_sub(4 + GUTTER_WIDTH),
+                        name_col_width,
+                        Self::item_flags(state, &result.item),
+                        theme,
+                    ),
+                ];
 
                 if is_selected {
                     table_cells = table_cells.drain(..).map(|t| t.selected()).collect();
@@ -176,17 +489,17 @@ impl PluginRenderer {
             }
         }
 
-        table
+        (table, (first_row, last_row))
     }
 
-    /// Render all items table
+    /// Render all items table, returning it with the range of rendered items
     fn render_all_items(
         state: &mut PluginState,
         table_rows: usize,
         table_width: usize,
         theme: &Option<Theme>,
-    ) -> Table {
-        let mut table = Table::new().add_row(vec!["Directory/Session"]);
+    ) -> (Table, (usize, usize)) {
+        let mut table = Table::new().add_row(vec![" ", "Directory/Session"]);
         let items = state.display_items();
         let selected_index = state.selected_index();
 
@@ -195,16 +508,25 @@ impl PluginRenderer {
 
         let (first_row, last_row) =
             Self::calculate_render_range(table_rows, items.len(), selected_index);
+        let last_row = last_row.min(items.len());
 
         for i in first_row..last_row {
             if let Some(item) = items.get(i) {
                 let is_selected = Some(i) == selected_index;
-                let mut table_cells = vec![Self::render_item(
-                    item,
-                    table_width.saturating_sub(4),
-                    name_col_width,
-                    theme,
-                )];
+                let flags = ItemFlags {
+                    nested: Self::is_nested(&items, i),
+                    ..Self::item_flags(state, item)
+                };
+                let mut table_cells = vec![
+                    Self::gutter_cell(i - first_row),
+                    Self::render_item(
+                        item,
+                        table_width.saturating_sub(4 + GUTTER_WIDTH),
+                        name_col_width,
+                        flags,
+                        theme,
+                    ),
+                ];
 
                 if is_selected {
                     table_cells = table_cells.drain(..).map(|t| t.selected()).collect();
@@ -214,7 +536,23 @@ impl PluginRenderer {
             }
         }
 
-        table
+        (table, (first_row, last_row))
+    }
+
+    /// Check whether an item belongs to a folder, i.e. the closest header above it is a folder header
+    /// Items without a folder are listed before the folders of their section
+    fn is_nested(items: &[SessionItem], index: usize) -> bool {
+        if matches!(items[index], SessionItem::Header { .. }) {
+            return false;
+        }
+        items[..index]
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                SessionItem::Header { folder, .. } => Some(folder.is_some()),
+                _ => None,
+            })
+            .unwrap_or(false)
     }
 
     /// Calculate the width of the name column based on the longest session name
@@ -224,7 +562,8 @@ impl PluginRenderer {
             .filter_map(|item| match item {
                 SessionItem::ExistingSession { name, .. } => Some(name.len() + 2), // "● " prefix
                 SessionItem::ResurrectableSession { name, .. } => Some(name.len() + 2), // "↺ " prefix
-                SessionItem::Directory { .. } => None, // Directories don't use columnar format
+                // Directories and headers don't use columnar format
+                SessionItem::Directory { .. } | SessionItem::Header { .. } => None,
             })
             .max()
             .unwrap_or(0);
@@ -243,9 +582,10 @@ impl PluginRenderer {
         indices: &[usize],
         max_width: usize,
         name_col_width: usize,
+        flags: ItemFlags,
         theme: &Option<Theme>,
     ) -> Text {
-        let mut text = Self::render_item(item, max_width, name_col_width, theme);
+        let mut text = Self::render_item(item, max_width, name_col_width, flags, theme);
 
         // Apply search highlighting
         if !indices.is_empty() {
@@ -356,8 +696,13 @@ impl PluginRenderer {
                         })
                         .collect()
                 }
-                SessionItem::Directory { path, .. } => {
-                    // Directories don't use columnar format - handle truncation only
+                // Headers have no matched characters
+                SessionItem::Header { .. } => Vec::new(),
+                SessionItem::Directory { path, vanished, .. } => {
+                    // Directories don't use columnar format - handle truncation
+                    // and the vanished marker only
+                    let marker_len = if *vanished { 2 } else { 0 };
+                    let max_width = max_width.saturating_sub(marker_len);
                     if path.len() > max_width && max_width > 10 {
                         // Path is truncated with "..."
                         let truncated_start = path.len().saturating_sub(max_width - 3);
@@ -365,14 +710,15 @@ impl PluginRenderer {
                             .iter()
                             .filter_map(|&idx| {
                                 if idx >= truncated_start {
-                                    Some(idx - truncated_start + 3) // +3 for "..."
+                                    // +3 for "..."
+                                    Some(marker_len + idx - truncated_start + 3)
                                 } else {
                                     None // Index is in truncated part
                                 }
                             })
                             .collect()
                     } else {
-                        indices.to_vec()
+                        indices.iter().map(|&idx| marker_len + idx).collect()
                     }
                 }
             };
@@ -389,21 +735,47 @@ impl PluginRenderer {
         text
     }
 
+    /// Get the render flags for an item
+    fn item_flags(state: &PluginState, item: &SessionItem) -> ItemFlags {
+        match item {
+            SessionItem::Directory { .. } | SessionItem::Header { .. } => ItemFlags::default(),
+            _ => ItemFlags {
+                marked: state.is_marked(item.name()),
+                protected: state.session_manager().is_protected(item.name()),
+                ..ItemFlags::default()
+            },
+        }
+    }
+
     /// Render a session item with columnar alignment
+    /// Marked sessions replace their status glyph with a check mark,
+    /// protected sessions use diamonds instead of circles,
+    /// items nested under a folder are indented
     fn render_item(
         item: &SessionItem,
         max_width: usize,
         name_col_width: usize,
+        flags: ItemFlags,
         theme: &Option<Theme>,
     ) -> Text {
+        let indent = if flags.nested { "  " } else { "" };
+        let offset = indent.len();
+        let max_width = max_width.saturating_sub(offset);
+
         match item {
             SessionItem::ExistingSession {
                 name,
                 directory,
                 is_current,
             } => {
-                let prefix = if *is_current { "● " } else { "○ " };
-                let name_display = format!("{}{}", prefix, name);
+                let prefix = match (flags.marked, flags.protected, *is_current) {
+                    (true, _, _) => "✓ ",
+                    (false, true, true) => "◆ ",
+                    (false, true, false) => "◇ ",
+                    (false, false, true) => "● ",
+                    (false, false, false) => "○ ",
+                };
+                let name_display = format!("{}{}{}", indent, prefix, name);
 
                 // Calculate remaining width for directory (after name column + 2 char gap)
                 let dir_col_start = name_col_width + 2;
@@ -424,18 +796,25 @@ impl PluginRenderer {
                     "{:<width$}  {}",
                     name_display,
                     dir_display,
-                    width = name_col_width
+                    width = name_col_width + offset
                 );
 
                 // Color session name only (after bullet)
                 // Emphasis colors: 0=orange, 1=cyan, 2=green, 3=pink (theme-dependent)
                 let color_idx = if *is_current { 2 } else { 1 };
-                let name_end = 2 + name.len();
-                Text::new(&display_text).color_range(color_idx, 2..name_end)
+                let name_start = offset + 2;
+                let name_end = name_start + name.len();
+                Text::new(&display_text).color_range(color_idx, name_start..name_end)
             }
             SessionItem::ResurrectableSession { name, duration } => {
-                let prefix = "↺ ";
-                let name_display = format!("{}{}", prefix, name);
+                let prefix = if flags.marked {
+                    "✓ "
+                } else if flags.protected {
+                    "◈ "
+                } else {
+                    "↺ "
+                };
+                let name_display = format!("{}{}{}", indent, prefix, name);
 
                 // Format duration info for second column
                 let duration_str = format!("{} ago", humantime::format_duration(*duration));
@@ -445,31 +824,61 @@ impl PluginRenderer {
                     "{:<width$}  {}",
                     name_display,
                     duration_str,
-                    width = name_col_width
+                    width = name_col_width + offset
                 );
 
                 // Color just the session name portion in pink (color index 3)
-                let name_end = 2 + name.len();
+                let name_start = offset + 2;
+                let name_end = name_start + name.len();
                 if let Some(theme) = theme {
-                    theme.content(&display_text).color_range(3, 2..name_end)
+                    theme
+                        .content(&display_text)
+                        .color_range(3, name_start..name_end)
                 } else {
-                    Text::new(&display_text).color_range(3, 2..name_end)
+                    Text::new(&display_text).color_range(3, name_start..name_end)
                 }
             }
-            SessionItem::Directory { path, .. } => {
+            SessionItem::Directory { path, vanished, .. } => {
                 // Directories don't use columnar format - just display the path
+                // Vanished directories get a ✗ (the theme has no dim color)
+                let marker = if *vanished { "✗ " } else { "" };
+                let max_width = max_width.saturating_sub(marker.chars().count());
                 let display_path = if path.len() > max_width && max_width > 10 {
-                    format!("...{}", &path[path.len().saturating_sub(max_width - 3)..])
+                    format!(
+                        "{}{}...{}",
+                        indent,
+                        marker,
+                        &path[path.len().saturating_sub(max_width - 3)..]
+                    )
                 } else {
-                    path.to_string()
+                    format!("{}{}{}", indent, marker, path)
                 };
 
-                if let Some(theme) = theme {
+                let text = if let Some(theme) = theme {
                     theme.content(&display_path)
                 } else {
                     Text::new(&display_path)
+                };
+                if *vanished {
+                    text.color_range(0, offset..offset + 1)
+                } else {
+                    text
                 }
             }
+            SessionItem::Header {
+                folder,
+                count,
+                collapsed,
+                ..
+            } => {
+                // Sections in orange, folders in green
+                let marker = if *collapsed { "▸ " } else { "▾ " };
+                let label = item.name();
+                let display_text = format!("{}{}{} ({})", indent, marker, label, count);
+                let color_idx = if folder.is_some() { 2 } else { 0 };
+                let label_end = offset + 2 + label.chars().count();
+                Text::new(&display_text).color_range(color_idx, offset..label_end)
+            }
         }
     }
 
@@ -487,28 +896,119 @@ impl PluginRenderer {
         result
     }
 
-    /// Render help text on two rows (row 1: navigation, row 2: actions)
+    /// Key hint for an action in help text, e.g. `<Ctrl+r>`; None when the action is unbound
+    fn key_hint(state: &PluginState, action: Action, normal_mode: bool) -> Option<String> {
+        state
+            .keymap()
+            .label(action, normal_mode)
+            .map(|label| format!("<{}>", label))
+    }
+
+    /// Build a help row from key hints and descriptions, skipping unbound actions
+    fn help_row(entries: &[(Option<String>, &str)]) -> Text {
+        let bound: Vec<(&String, &str)> = entries
+            .iter()
+            .filter_map(|(hint, description)| hint.as_ref().map(|hint| (hint, *description)))
+            .collect();
+        let row = bound
+            .iter()
+            .map(|(hint, description)| format!("{} {}", hint, description))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let keys: Vec<&str> = bound.iter().map(|(hint, _)| hint.as_str()).collect();
+        Self::color_keys(&row, &keys, 0)
+    }
+
+    /// Render help text on two rows (row 1: navigation, row 2: actions) from the active keymap
     fn render_help_text(state: &PluginState, x: usize, y: usize, _theme: &Option<Theme>) {
-        let (row1, row2, keys1, keys2) = if state.display_items().is_empty() {
+        let normal_mode = state.is_normal_mode();
+        let hint = |action| Self::key_hint(state, action, normal_mode);
+
+        // Switching between search input and normal mode (vim preset only)
+        let mode_entry = if !state.keymap().has_normal_mode() {
+            (None, "")
+        } else if normal_mode {
+            (hint(Action::InsertMode), "Search")
+        } else {
+            (hint(Action::NormalMode), "Normal")
+        };
+
+        let (row1, row2) = if state.display_items().is_empty() {
             (
-                "<Enter> Create, <Esc> Exit",
-                "<Ctrl+Enter> Quick create",
-                vec!["<Enter>", "<Esc>"],
-                vec!["<Ctrl+Enter>"],
+                vec![
+                    (hint(Action::Switch), "Create"),
+                    mode_entry,
+                    (hint(Action::Back), "Exit"),
+                ],
+                vec![(hint(Action::QuickCreate), "Quick create")],
             )
         } else {
+            // Navigation keys are shown together, e.g. <↑↓> or <k/j>
+            let navigate = match (
+                state.keymap().label(Action::Up, normal_mode),
+                state.keymap().label(Action::Down, normal_mode),
+            ) {
+                (Some(up), Some(down)) if up.chars().count() == 1 && down.chars().count() == 1 => {
+                    Some(format!("<{}{}>", up, down))
+                }
+                (Some(up), Some(down)) => Some(format!("<{}/{}>", up, down)),
+                _ => None,
+            };
+            let mut row2 = vec![
+                (hint(Action::QuickCreate), "Quick"),
+                (hint(Action::Rename), "Rename"),
+                (hint(Action::ToggleDead), "Dead"),
+                (hint(Action::ToggleGroup), "Fold"),
+                (hint(Action::Reload), "Reload"),
+                (hint(Action::Kill), "Kill"),
+                (hint(Action::Protect), "Protect"),
+            ];
+            if matches!(state.selected_item(), Some(SessionItem::Directory { .. })) {
+                row2.push((hint(Action::OpenTab), "Tab"));
+                row2.push((hint(Action::OpenPane), "Pane"));
+                row2.push((hint(Action::OpenFloating), "Float"));
+            }
+            if state.has_vanished_directories() {
+                row2.push((hint(Action::PruneAll), "Prune ✗"));
+            }
+            if state.skipped_line_count() > 0 {
+                row2.push((hint(Action::Diagnostics), "Diagnostics"));
+            }
             (
-                "<↑↓> Navigate, <Enter> Switch/New, <Esc> Exit",
-                "<Ctrl+Enter> Quick, <Alt+r> Rename, <Alt+d> Dead, <Ctrl+r> Reload, <Del> Kill",
-                vec!["<↑↓>", "<Enter>", "<Esc>"],
-                vec!["<Ctrl+Enter>", "<Alt+r>", "<Alt+d>", "<Ctrl+r>", "<Del>"],
+                vec![
+                    (navigate, "Navigate"),
+                    (hint(Action::Switch), "Switch/New"),
+                    (hint(Action::Mark), "Mark"),
+                    (hint(Action::MarkAll), "Mark all"),
+                    mode_entry,
+                    (hint(Action::Back), "Exit"),
+                ],
+                row2,
             )
         };
 
-        let text1 = Self::color_keys(row1, &keys1, 0);
-        let text2 = Self::color_keys(row2, &keys2, 0);
-        print_text_with_coordinates(text1, x, y, None, None);
-        print_text_with_coordinates(text2, x, y + 1, None, None);
+        print_text_with_coordinates(Self::help_row(&row1), x, y, None, None);
+        print_text_with_coordinates(Self::help_row(&row2), x, y + 1, None, None);
+    }
+
+    /// Render notice for a deletion that can still be undone
+    fn render_undo_notice(state: &PluginState, deletion: &UndoableDeletion, x: usize, y: usize) {
+        let verb = if deletion.has_killed_sessions() {
+            "Killed"
+        } else {
+            "Deleted"
+        };
+        let mut notice = match deletion.session_names().as_slice() {
+            [session_name] => format!("{} '{}'", verb, session_name),
+            names => format!("{} {} sessions", verb, names.len()),
+        };
+        let undo_hint = Self::key_hint(state, Action::Undo, state.is_normal_mode());
+        if let Some(hint) = &undo_hint {
+            notice.push_str(&format!(" - {} Undo", hint));
+        }
+        let keys: Vec<&str> = undo_hint.iter().map(String::as_str).collect();
+        let text = Self::color_keys(&notice, &keys, 0);
+        print_text_with_coordinates(text, x, y, None, None);
     }
 
     /// Render error message
@@ -518,9 +1018,10 @@ impl PluginRenderer {
         print_text_with_coordinates(error_text, x, dialog_y, None, None);
     }
 
-    /// Render deletion confirmation dialog
+    /// Render deletion confirmation dialog listing every affected session
     fn render_deletion_confirmation(
-        session_name: &str,
+        session_names: &[String],
+        state: &PluginState,
         x: usize,
         y: usize,
         width: usize,
@@ -530,16 +1031,72 @@ impl PluginRenderer {
         let dialog_width = std::cmp::min(60, width.saturating_sub(4));
         let content_width = dialog_width.saturating_sub(4); // Space for borders + padding
 
-        let message = format!("Kill session '{}'?", session_name);
-        let warning =
-            "If this is a resurrectable session, it will be deleted. This action cannot be undone.";
-        let prompt = "Press 'y' to confirm, 'n' or Esc to cancel";
+        let message = match session_names {
+            [session_name] => format!("Kill session '{}'?", session_name),
+            _ => format!("Kill {} sessions?", session_names.len()),
+        };
+        let mut warning = if session_names.len() > 1 {
+            "Resurrectable sessions will be deleted.".to_string()
+        } else {
+            "If this is a resurrectable session, it will be deleted.".to_string()
+        };
+        if let Some(hint) = Self::key_hint(state, Action::Undo, state.is_normal_mode()) {
+            warning.push_str(&format!(" Press {} right after to undo.", hint));
+        }
+        let confirm_label = state.keymap().label(Action::Confirm, false);
+        let cancel_label = state.keymap().label(Action::Cancel, false);
+        let typed_prompt;
+        let prompt = match state.session_manager().typed_confirmation_target() {
+            Some(_) => {
+                typed_prompt = format!(
+                    "Protected - type its name and {}: {}_",
+                    confirm_label.as_deref().unwrap_or("confirm"),
+                    state.session_manager().typed_confirmation()
+                );
+                &typed_prompt
+            }
+            None => {
+                typed_prompt = match &cancel_label {
+                    Some(cancel) => format!("Press 'y' to confirm, 'n' or {} to cancel", cancel),
+                    None => "Press 'y' to confirm, 'n' to cancel".to_string(),
+                };
+                &typed_prompt
+            }
+        };
 
         // Wrap warning text to fit dialog
-        let wrapped_warning = Self::wrap_text(warning, content_width);
+        let wrapped_warning = Self::wrap_text(&warning, content_width);
+
+        // List affected sessions for batch deletion, leaving room for the rest of the dialog
+        let mut session_lines = Vec::new();
+        if session_names.len() > 1 {
+            let max_listed = height.saturating_sub(6 + wrapped_warning.len()).max(1);
+            let is_resurrectable = |name: &str| {
+                state
+                    .session_manager()
+                    .resurrectable_sessions()
+                    .iter()
+                    .any(|(n, _)| n == name)
+            };
+            for name in session_names.iter().take(max_listed) {
+                let prefix = if is_resurrectable(name) { "↺" } else { "○" };
+                let mut line = format!("{} {}", prefix, name);
+                if line.chars().count() > content_width {
+                    line = line.chars().take(content_width).collect();
+                }
+                session_lines.push(line);
+            }
+            if session_names.len() > max_listed {
+                session_lines.pop();
+                session_lines.push(format!(
+                    "... and {} more",
+                    session_names.len() - max_listed + 1
+                ));
+            }
+        }
 
-        // Dynamic height: top border + message + warning lines + blank + prompt + bottom border
-        let dialog_height = 4 + wrapped_warning.len();
+        // Dynamic height: top border + message + sessions + warning lines + blank + prompt + bottom border
+        let dialog_height = 4 + session_lines.len() + wrapped_warning.len();
         let dialog_x = x + (width.saturating_sub(dialog_width)) / 2;
         let dialog_y = y + (height.saturating_sub(dialog_height)) / 2;
 
@@ -551,6 +1108,15 @@ impl PluginRenderer {
             format!("│{:^width$}│", message, width = inner_width),
         ];
 
+        // Add affected sessions (left-aligned so names line up)
+        for line in &session_lines {
+            dialog_lines.push(format!(
+                "│  {:<width$}│",
+                line,
+                width = inner_width.saturating_sub(2)
+            ));
+        }
+
         // Add wrapped warning lines
         for line in &wrapped_warning {
             dialog_lines.push(format!("│{:^width$}│", line, width = inner_width));
@@ -604,7 +1170,22 @@ impl PluginRenderer {
         (x, y, width, height)
     }
 
+    /// Position of the selected item and number of items (e.g. "12/340"), counting only
+    /// sessions and directories; just the number if nothing or a header row is selected
+    fn position_indicator(items: &[SessionItem], selected_index: Option<usize>) -> String {
+        let is_entry = |item: &SessionItem| !matches!(item, SessionItem::Header { .. });
+        let total = items.iter().filter(|item| is_entry(item)).count();
+        match selected_index {
+            Some(index) if items.get(index).is_some_and(is_entry) => {
+                let position = items[..=index].iter().filter(|item| is_entry(item)).count();
+                format!("{}/{}", position, total)
+            }
+            _ => format!("{}", total),
+        }
+    }
+
     /// Calculate which rows to render for pagination
+    /// The window is centered on the selection and kept full at the end of the list
     fn calculate_render_range(
         table_rows: usize,
         items_len: usize,
@@ -614,11 +1195,104 @@ impl PluginRenderer {
             let row_count_to_render = table_rows.saturating_sub(1); // 1 for the title
             let first_row_index = selected_index
                 .unwrap_or(0)
-                .saturating_sub(row_count_to_render / 2);
+                .saturating_sub(row_count_to_render / 2)
+                .min(items_len - row_count_to_render);
             let last_row_index = first_row_index + row_count_to_render;
             (first_row_index, last_row_index)
         } else {
             (0, items_len)
         }
     }
+
+    /// Position and size of the scrollbar thumb within a track of `visible` rows
+    fn scrollbar_thumb(visible: usize, first_row: usize, items_len: usize) -> (usize, usize) {
+        if items_len == 0 || visible == 0 {
+            return (0, 0);
+        }
+        let size = (visible * visible / items_len).clamp(1, visible);
+        let start = (first_row * visible / items_len).min(visible - size);
+        (start, size)
+    }
+
+    /// Render a vertical scrollbar for the rendered window of a list
+    fn render_scrollbar(x: usize, y: usize, visible: usize, first_row: usize, items_len: usize) {
+        let (thumb_start, thumb_size) = Self::scrollbar_thumb(visible, first_row, items_len);
+        for row in 0..visible {
+            let text = if (thumb_start..thumb_start + thumb_size).contains(&row) {
+                Text::new("┃").color_range(0, ..)
+            } else {
+                Text::new("│")
+            };
+            print_text_with_coordinates(text, x, y + row, None, None);
+        }
+    }
+
+    /// Quick-select number (`Alt+1`..`Alt+9`) for a visible row
+    fn gutter_cell(position: usize) -> Text {
+        if position < 9 {
+            Text::new((position + 1).to_string()).color_range(3, ..)
+        } else {
+            Text::new(" ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_range_stays_full_at_end_of_list() {
+        // 10 table rows = title + 9 items
+        assert_eq!(
+            PluginRenderer::calculate_render_range(10, 5, Some(4)),
+            (0, 5)
+        );
+        assert_eq!(
+            PluginRenderer::calculate_render_range(10, 100, Some(0)),
+            (0, 9)
+        );
+        assert_eq!(
+            PluginRenderer::calculate_render_range(10, 100, Some(50)),
+            (46, 55)
+        );
+        assert_eq!(
+            PluginRenderer::calculate_render_range(10, 100, Some(99)),
+            (91, 100)
+        );
+    }
+
+    #[test]
+    fn test_position_indicator_skips_headers() {
+        use crate::session::ItemGroup;
+        let header = |group| SessionItem::Header {
+            group,
+            folder: None,
+            count: 1,
+            collapsed: false,
+        };
+        let directory = |path: &str| SessionItem::Directory {
+            path: path.to_string(),
+            session_name: path.to_string(),
+            vanished: false,
+        };
+        let items = vec![
+            header(ItemGroup::Sessions),
+            directory("/a"),
+            header(ItemGroup::Directories),
+            directory("/b"),
+        ];
+        assert_eq!(PluginRenderer::position_indicator(&items, Some(3)), "2/2");
+        assert_eq!(PluginRenderer::position_indicator(&items, Some(2)), "2");
+        assert_eq!(PluginRenderer::position_indicator(&[], None), "0");
+    }
+
+    #[test]
+    fn test_scrollbar_thumb() {
+        assert_eq!(PluginRenderer::scrollbar_thumb(10, 0, 100), (0, 1));
+        assert_eq!(PluginRenderer::scrollbar_thumb(10, 90, 100), (9, 1));
+        assert_eq!(PluginRenderer::scrollbar_thumb(10, 0, 20), (0, 5));
+        assert_eq!(PluginRenderer::scrollbar_thumb(10, 10, 20), (5, 5));
+        assert_eq!(PluginRenderer::scrollbar_thumb(0, 0, 20), (0, 0));
+    }
 }
@@ -0,0 +1,65 @@
+use super::ZoxideDirectory;
+
+/// Serialize processed directories for the startup cache
+/// One "ranking<TAB>vanished<TAB>session name<TAB>path" line per directory
+pub fn serialize(directories: &[ZoxideDirectory]) -> String {
+    directories
+        .iter()
+        // Line breaks or tabs in a name would break the line format; such entries are re-read on startup
+        .filter(|dir| !dir.directory.contains('\n') && !dir.session_name.contains(['\t', '\n']))
+        .map(|dir| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                dir.ranking,
+                u8::from(dir.vanished),
+                dir.session_name,
+                dir.directory
+            )
+        })
+        .collect()
+}
+
+/// Read directories written by `serialize`, skipping malformed lines
+pub fn deserialize(text: &str) -> Vec<ZoxideDirectory> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let ranking = fields.next()?.parse::<f64>().ok()?;
+            let vanished = fields.next()? == "1";
+            let session_name = fields.next()?.to_string();
+            let directory = fields.next().filter(|path| !path.is_empty())?.to_string();
+            Some(ZoxideDirectory {
+                ranking,
+                directory,
+                session_name,
+                vanished,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let directories = vec![
+            ZoxideDirectory {
+                ranking: 12.5,
+                directory: "/home/u/my\tproject".to_string(),
+                session_name: "my.project".to_string(),
+                vanished: false,
+            },
+            ZoxideDirectory {
+                ranking: 1.0,
+                directory: "/gone".to_string(),
+                session_name: "gone".to_string(),
+                vanished: true,
+            },
+        ];
+        let text = serialize(&directories);
+        assert_eq!(deserialize(&text), directories);
+        assert_eq!(deserialize("garbage\n\n3\t0\tname\n"), Vec::new());
+    }
+}
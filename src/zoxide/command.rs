@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+/// How zoxide is invoked: binary, database location, extra query arguments and environment
+#[derive(Debug, Clone)]
+pub struct ZoxideCommand {
+    /// Path or name of the zoxide binary
+    pub binary: String,
+    /// Extra arguments for `zoxide query` (e.g. `--all`, `--exclude <path>`)
+    pub query_args: Vec<String>,
+    /// Environment variables for every zoxide invocation, including `_ZO_DATA_DIR`
+    pub env: BTreeMap<String, String>,
+}
+
+impl Default for ZoxideCommand {
+    fn default() -> Self {
+        Self {
+            binary: "zoxide".to_string(),
+            query_args: Vec::new(),
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+impl ZoxideCommand {
+    /// Build from the `zoxide_path`, `zoxide_data_dir`, `zoxide_args` and `zoxide_env` config
+    /// `zoxide_env` entries without `=` are returned as errors
+    pub fn from_config(
+        binary: Option<&str>,
+        data_dir: Option<&str>,
+        args: Option<&str>,
+        env: &[String],
+    ) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut variables = BTreeMap::new();
+        for entry in env {
+            match entry.split_once('=') {
+                Some((name, value)) if !name.trim().is_empty() => {
+                    variables.insert(name.trim().to_string(), value.to_string());
+                }
+                _ => errors.push(format!("Invalid zoxide_env entry '{}'", entry)),
+            }
+        }
+        if let Some(data_dir) = data_dir {
+            variables.insert("_ZO_DATA_DIR".to_string(), data_dir.to_string());
+        }
+
+        let command = Self {
+            binary: binary
+                .map(str::trim)
+                .filter(|binary| !binary.is_empty())
+                .unwrap_or("zoxide")
+                .to_string(),
+            query_args: args
+                .map(|args| args.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            env: variables,
+        };
+        (command, errors)
+    }
+
+    /// `zoxide query -l -s` with the extra query arguments
+    pub fn query(&self) -> Vec<String> {
+        let mut command = self.args(&["query", "-l", "-s"]);
+        command.extend(self.query_args.iter().cloned());
+        command
+    }
+
+    /// `zoxide query <terms>...` (best match for the terms) with the extra query arguments
+    pub fn query_terms(&self, terms: &[&str]) -> Vec<String> {
+        let mut command = self.args(&["query"]);
+        command.extend(self.query_args.iter().cloned());
+        command.push("--".to_string());
+        command.extend(terms.iter().map(|term| term.to_string()));
+        command
+    }
+
+    /// `zoxide add <directory>`
+    pub fn add(&self, directory: &str) -> Vec<String> {
+        self.args(&["add", directory])
+    }
+
+    /// `zoxide remove <paths>...`
+    pub fn remove(&self, paths: &[String]) -> Vec<String> {
+        let mut command = self.args(&["remove"]);
+        command.extend(paths.iter().cloned());
+        command
+    }
+
+    /// The binary followed by arguments
+    fn args(&self, args: &[&str]) -> Vec<String> {
+        std::iter::once(self.binary.as_str())
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Format a command with its environment the way it would be typed in a shell, for error messages
+pub fn display_command(command: &[String], env: &BTreeMap<String, String>) -> String {
+    let quote = |word: &str| {
+        if !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./=:+,@%".contains(c))
+        {
+            word.to_string()
+        } else {
+            format!("'{}'", word.replace('\'', "'\\''"))
+        }
+    };
+    env.iter()
+        .map(|(name, value)| format!("{}={}", name, quote(value)))
+        .chain(command.iter().map(|word| quote(word)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let (command, errors) = ZoxideCommand::from_config(
+            Some("/nix/profile/bin/zoxide"),
+            Some("/data/zoxide"),
+            Some("--all  --exclude /tmp"),
+            &["FOO=bar=baz".to_string(), "broken".to_string()],
+        );
+        assert_eq!(errors, vec!["Invalid zoxide_env entry 'broken'"]);
+        assert_eq!(
+            command.query(),
+            vec![
+                "/nix/profile/bin/zoxide",
+                "query",
+                "-l",
+                "-s",
+                "--all",
+                "--exclude",
+                "/tmp"
+            ]
+        );
+        assert_eq!(command.env.get("FOO").map(String::as_str), Some("bar=baz"));
+        assert_eq!(
+            command.env.get("_ZO_DATA_DIR").map(String::as_str),
+            Some("/data/zoxide")
+        );
+        assert_eq!(
+            command.query_terms(&["web", "api"]),
+            vec![
+                "/nix/profile/bin/zoxide",
+                "query",
+                "--all",
+                "--exclude",
+                "/tmp",
+                "--",
+                "web",
+                "api"
+            ]
+        );
+        // Extra arguments only apply to queries
+        assert_eq!(
+            command.add("/a"),
+            vec!["/nix/profile/bin/zoxide", "add", "/a"]
+        );
+    }
+
+    #[test]
+    fn test_display_command_quotes_words() {
+        let mut env = BTreeMap::new();
+        env.insert("_ZO_DATA_DIR".to_string(), "/my data".to_string());
+        let command = ZoxideCommand::default().remove(&["/it's".to_string()]);
+        assert_eq!(
+            display_command(&command, &env),
+            "_ZO_DATA_DIR='/my data' zoxide remove '/it'\\''s'"
+        );
+    }
+}
@@ -9,6 +9,9 @@ pub struct ZoxideDirectory {
     pub directory: String,
     /// Generated session name for this directory
     pub session_name: String,
+    /// The directory no longer exists on disk
+    #[serde(default)]
+    pub vanished: bool,
 }
 
 impl Ord for ZoxideDirectory {
@@ -31,3 +34,80 @@ impl PartialOrd for ZoxideDirectory {
 impl Eq for ZoxideDirectory {}
 
 impl ZoxideDirectory {}
+
+/// Mark directories that no longer exist as vanished
+/// If none of them exist the check is assumed to be unavailable and nothing is marked
+pub fn mark_vanished(directories: &mut [ZoxideDirectory], exists: impl Fn(&str) -> bool) {
+    let vanished: Vec<bool> = directories
+        .iter()
+        .map(|dir| !exists(&dir.directory))
+        .collect();
+    if vanished.iter().all(|&gone| gone) {
+        return;
+    }
+    for (dir, gone) in directories.iter_mut().zip(vanished) {
+        dir.vanished = gone;
+    }
+}
+
+/// Score zoxide adds for each visit of a directory
+const ZOXIDE_ADD_SCORE: f64 = 1.0;
+
+/// Mirror `zoxide add` locally: bump a directory's score and keep the list sorted by score
+/// Returns false if the directory is not listed
+pub fn bump_ranking(directories: &mut [ZoxideDirectory], path: &str) -> bool {
+    let Some(dir) = directories.iter_mut().find(|dir| dir.directory == path) else {
+        return false;
+    };
+    dir.ranking += ZOXIDE_ADD_SCORE;
+    // Stable sort keeps the relative order of equal scores
+    directories.sort_by(|a, b| {
+        b.ranking
+            .partial_cmp(&a.ranking)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directories(paths: &[&str]) -> Vec<ZoxideDirectory> {
+        paths
+            .iter()
+            .map(|path| ZoxideDirectory {
+                directory: path.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_mark_vanished() {
+        let mut dirs = directories(&["/a", "/gone", "/b"]);
+        mark_vanished(&mut dirs, |path| path != "/gone");
+        let vanished: Vec<bool> = dirs.iter().map(|dir| dir.vanished).collect();
+        assert_eq!(vanished, vec![false, true, false]);
+
+        // Nothing readable: the filesystem is not mapped, keep every directory
+        let mut dirs = directories(&["/a", "/b"]);
+        mark_vanished(&mut dirs, |_| false);
+        assert!(dirs.iter().all(|dir| !dir.vanished));
+    }
+
+    #[test]
+    fn test_bump_ranking_resorts() {
+        let mut dirs = directories(&["/a", "/b", "/c"]);
+        for (dir, ranking) in dirs.iter_mut().zip([3.0, 2.5, 1.0]) {
+            dir.ranking = ranking;
+        }
+
+        assert!(bump_ranking(&mut dirs, "/b"));
+        let order: Vec<&str> = dirs.iter().map(|dir| dir.directory.as_str()).collect();
+        assert_eq!(order, vec!["/b", "/a", "/c"]);
+        assert_eq!(dirs[0].ranking, 3.5);
+
+        assert!(!bump_ranking(&mut dirs, "/missing"));
+    }
+}
@@ -0,0 +1,82 @@
+/// Command listing directories under a root whose name contains the query
+/// Uses `fd` when installed and falls back to `find`; both stop at `depth` levels and `limit` results
+pub fn search_command(query: &str, root: &str, depth: usize, limit: usize) -> Vec<String> {
+    let script = concat!(
+        "q=$1; depth=$2; limit=$3; root=$4; pattern=$5; ",
+        "case $root in \"~\") root=$HOME;; \"~/\"*) root=\"$HOME/${root#\"~/\"}\";; esac; ",
+        "if command -v fd >/dev/null 2>&1; then ",
+        "fd --type d --hidden --exclude .git --ignore-case --fixed-strings ",
+        "--max-depth \"$depth\" --max-results \"$limit\" -- \"$q\" \"$root\"; ",
+        "else ",
+        "find \"$root\" -maxdepth \"$depth\" -type d -iname \"*$pattern*\" ",
+        "-not -path '*/.git/*' 2>/dev/null | head -n \"$limit\"; ",
+        "fi"
+    );
+    [
+        "sh",
+        "-c",
+        script,
+        "sh",
+        query,
+        &depth.to_string(),
+        &limit.to_string(),
+        root,
+        &glob_escape(query),
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+/// Escape glob metacharacters so `find -iname` matches the query literally, like `fd --fixed-strings`
+fn glob_escape(query: &str) -> String {
+    let mut escaped = String::with_capacity(query.len());
+    for c in query.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Parse the directories printed by `search_command`, without trailing slashes
+pub fn parse_output(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| match line.trim_end_matches('/') {
+            "" => "/".to_string(),
+            path => path.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_command_passes_query_as_argument() {
+        let command = search_command("it's $(x)", "~/src", 4, 50);
+        assert_eq!(&command[..2], ["sh", "-c"]);
+        assert_eq!(
+            &command[3..],
+            ["sh", "it's $(x)", "4", "50", "~/src", "it's $(x)"]
+        );
+    }
+
+    #[test]
+    fn test_find_pattern_is_escaped() {
+        assert_eq!(glob_escape("a*b?[c]\\d"), "a\\*b\\?\\[c\\]\\\\d");
+        assert_eq!(glob_escape("web app"), "web app");
+    }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(
+            parse_output("/home/u/src/webapp/\n\n/home/u/my app\n"),
+            vec!["/home/u/src/webapp", "/home/u/my app"]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use super::ZoxideDirectory;
+use crate::pattern::PathPattern;
+
+/// Limits on which zoxide directories are listed
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryFilter {
+    /// Paths that are never listed
+    pub exclude: Vec<PathPattern>,
+    /// If not empty, only paths matching one of these are listed
+    pub include: Vec<PathPattern>,
+    /// Directories scoring below this are not listed
+    pub min_score: Option<f64>,
+    /// List at most this many directories (highest scores first)
+    pub max_entries: Option<usize>,
+    /// Problems found while parsing the patterns
+    pub errors: Vec<String>,
+}
+
+impl DirectoryFilter {
+    /// Build a filter from pipe-separated pattern lists, collecting invalid patterns as errors
+    pub fn new(
+        exclude: &[String],
+        include: &[String],
+        min_score: Option<f64>,
+        max_entries: Option<usize>,
+    ) -> Self {
+        let mut errors = Vec::new();
+        let mut parse = |patterns: &[String]| -> Vec<PathPattern> {
+            patterns
+                .iter()
+                .filter_map(|pattern| {
+                    PathPattern::parse(pattern)
+                        .map_err(|err| errors.push(err))
+                        .ok()
+                })
+                .collect()
+        };
+        let exclude = parse(exclude);
+        let include = parse(include);
+
+        Self {
+            exclude,
+            include,
+            min_score,
+            max_entries,
+            errors,
+        }
+    }
+
+    /// Check whether a directory passes the path and score filters
+    pub fn allows(&self, directory: &ZoxideDirectory) -> bool {
+        let path = directory.directory.as_str();
+        self.min_score.is_none_or(|min| directory.ranking >= min)
+            && !self.exclude.iter().any(|pattern| pattern.is_match(path))
+            && (self.include.is_empty()
+                || self.include.iter().any(|pattern| pattern.is_match(path)))
+    }
+
+    /// Drop filtered directories and cap the rest, keeping the highest scores
+    /// Directories must already be sorted by score (highest first)
+    pub fn apply(&self, directories: &mut Vec<ZoxideDirectory>) {
+        directories.retain(|directory| self.allows(directory));
+        if let Some(max_entries) = self.max_entries {
+            directories.truncate(max_entries);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(path: &str, ranking: f64) -> ZoxideDirectory {
+        ZoxideDirectory {
+            ranking,
+            directory: path.to_string(),
+            session_name: String::new(),
+            vanished: false,
+        }
+    }
+
+    fn list(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let filter = DirectoryFilter::new(
+            &list(&["*/node_modules*", "re:/target$"]),
+            &list(&["/home/u/*"]),
+            None,
+            None,
+        );
+        assert!(filter.errors.is_empty());
+        assert!(filter.allows(&directory("/home/u/app", 1.0)));
+        assert!(!filter.allows(&directory("/home/u/app/node_modules", 1.0)));
+        assert!(!filter.allows(&directory("/home/u/app/target", 1.0)));
+        assert!(!filter.allows(&directory("/tmp/scratch", 1.0)));
+    }
+
+    #[test]
+    fn test_min_score_and_max_entries() {
+        let filter = DirectoryFilter::new(&[], &[], Some(2.0), Some(2));
+        let mut directories = vec![
+            directory("/a", 9.0),
+            directory("/b", 5.0),
+            directory("/c", 3.0),
+            directory("/d", 1.0),
+        ];
+        filter.apply(&mut directories);
+        let paths: Vec<_> = directories.iter().map(|d| d.directory.as_str()).collect();
+        assert_eq!(paths, vec!["/a", "/b"]);
+    }
+
+    #[test]
+    fn test_invalid_regex_is_reported() {
+        let filter = DirectoryFilter::new(&list(&["re:[", "/tmp*"]), &[], None, None);
+        assert_eq!(filter.errors.len(), 1);
+        assert_eq!(filter.exclude.len(), 1);
+    }
+}
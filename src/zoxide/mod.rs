@@ -1,5 +1,14 @@
+pub mod cache;
+pub mod command;
 pub mod directory;
+pub mod disk;
+pub mod filter;
+pub mod parser;
 pub mod search;
+pub mod source;
 
-pub use directory::ZoxideDirectory;
+pub use command::{display_command, ZoxideCommand};
+pub use directory::{bump_ranking, mark_vanished, ZoxideDirectory};
+pub use filter::DirectoryFilter;
 pub use search::SearchEngine;
+pub use source::{DirectorySource, SourceDiagnostics};
@@ -0,0 +1,227 @@
+use super::ZoxideDirectory;
+
+/// Line format of a directory source's output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineFormat {
+    /// "score path", the score optionally padded with spaces (zoxide, fasd)
+    Scored,
+    /// "score:<TAB>path", followed by a line of underscores and totals (autojump)
+    Autojump,
+    /// "path|rank|time" (z database)
+    Z,
+    /// "path" or "score path" (files and custom commands)
+    Paths,
+}
+
+/// A line of output that could not be turned into a directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedLine {
+    /// 1-based line number in the output
+    pub line: usize,
+    /// Line content, with invalid UTF-8 replaced
+    pub content: String,
+    /// Why the line was skipped
+    pub reason: &'static str,
+}
+
+/// Directories parsed from a source's output, and the lines that were skipped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseResult {
+    pub directories: Vec<ZoxideDirectory>,
+    pub skipped: Vec<SkippedLine>,
+}
+
+/// Parse raw command output
+/// Paths are kept verbatim (including leading and trailing spaces), blank lines are ignored
+pub fn parse(format: LineFormat, output: &[u8]) -> ParseResult {
+    let mut result = ParseResult::default();
+
+    for (index, line) in output.split(|&byte| byte == b'\n').enumerate() {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        // autojump prints totals after a line of underscores
+        if format == LineFormat::Autojump && line.iter().all(|&byte| byte == b'_') {
+            break;
+        }
+
+        match parse_line(format, line) {
+            Ok((ranking, directory)) => result.directories.push(ZoxideDirectory {
+                ranking,
+                directory,
+                ..Default::default()
+            }),
+            Err(reason) => result.skipped.push(SkippedLine {
+                line: index + 1,
+                content: String::from_utf8_lossy(line).to_string(),
+                reason,
+            }),
+        }
+    }
+
+    result
+}
+
+/// Parse one non-blank line into a score and a path
+fn parse_line(format: LineFormat, line: &[u8]) -> Result<(f64, String), &'static str> {
+    match format {
+        LineFormat::Scored => {
+            let (score, path) = split_score(line).ok_or("missing path")?;
+            Ok((parse_score(score)?, parse_path(path)?))
+        }
+        LineFormat::Autojump => {
+            let separator = line
+                .windows(2)
+                .position(|pair| pair == b":\t")
+                .ok_or("missing ':<TAB>' separator")?;
+            let score = trim_ascii(&line[..separator]);
+            Ok((parse_score(score)?, parse_path(&line[separator + 2..])?))
+        }
+        LineFormat::Z => {
+            // Split from the right: paths may contain '|'
+            let mut fields = line.rsplitn(3, |&byte| byte == b'|');
+            let _time = fields.next();
+            let rank = fields.next().ok_or("missing rank")?;
+            let path = fields.next().ok_or("missing rank")?;
+            Ok((parse_score(rank)?, parse_path(path)?))
+        }
+        LineFormat::Paths => match split_score(line) {
+            Some((score, path)) if parse_score(score).is_ok() => {
+                Ok((parse_score(score)?, parse_path(path)?))
+            }
+            _ => Ok((0.0, parse_path(line)?)),
+        },
+    }
+}
+
+/// Split "  score path" at the single space after the score
+fn split_score(line: &[u8]) -> Option<(&[u8], &[u8])> {
+    let start = line
+        .iter()
+        .position(|&byte| byte != b' ' && byte != b'\t')?;
+    let line = &line[start..];
+    let end = line
+        .iter()
+        .position(|&byte| byte == b' ' || byte == b'\t')?;
+    Some((&line[..end], &line[end + 1..]))
+}
+
+/// Parse a finite score
+fn parse_score(score: &[u8]) -> Result<f64, &'static str> {
+    std::str::from_utf8(score)
+        .ok()
+        .and_then(|score| score.parse::<f64>().ok())
+        .filter(|score| score.is_finite())
+        .ok_or("invalid score")
+}
+
+/// Keep a path verbatim, rejecting empty and non-UTF-8 paths
+fn parse_path(path: &[u8]) -> Result<String, &'static str> {
+    if path.is_empty() {
+        return Err("missing path");
+    }
+    String::from_utf8(path.to_vec()).map_err(|_| "path is not valid UTF-8")
+}
+
+/// Trim ASCII whitespace from both ends
+fn trim_ascii(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+    &bytes[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(result: &ParseResult) -> Vec<(f64, &str)> {
+        result
+            .directories
+            .iter()
+            .map(|dir| (dir.ranking, dir.directory.as_str()))
+            .collect()
+    }
+
+    fn reasons(result: &ParseResult) -> Vec<(usize, &str)> {
+        result
+            .skipped
+            .iter()
+            .map(|skipped| (skipped.line, skipped.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_padded_scores() {
+        let result = parse(LineFormat::Scored, b"  12.5 /a\n 100.0 /b\n3 /c\n");
+        assert_eq!(
+            entries(&result),
+            vec![(12.5, "/a"), (100.0, "/b"), (3.0, "/c")]
+        );
+        assert!(result.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_paths_with_spaces_are_kept_verbatim() {
+        let result = parse(
+            LineFormat::Scored,
+            b"   4.0 /home/u/my project\n   2.0  /lead\n   1.0 /trail \r\n",
+        );
+        assert_eq!(
+            entries(&result),
+            vec![
+                (4.0, "/home/u/my project"),
+                (2.0, " /lead"),
+                (1.0, "/trail ")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_lines_are_reported() {
+        let result = parse(
+            LineFormat::Scored,
+            b"  1.0 /ok\nabc /bad-score\n  7.0\n\n  2.0 /caf\xe9\n  NaN /nan\n",
+        );
+        assert_eq!(entries(&result), vec![(1.0, "/ok")]);
+        assert_eq!(
+            reasons(&result),
+            vec![
+                (2, "invalid score"),
+                (3, "missing path"),
+                (5, "path is not valid UTF-8"),
+                (6, "invalid score"),
+            ]
+        );
+        assert_eq!(result.skipped[2].content, "  2.0 /caf\u{fffd}");
+    }
+
+    #[test]
+    fn test_autojump_stops_at_totals() {
+        let result = parse(
+            LineFormat::Autojump,
+            b"10.0:\t/a b\n22.4:\t/c\n________\n\n32:\t total weight\n",
+        );
+        assert_eq!(entries(&result), vec![(10.0, "/a b"), (22.4, "/c")]);
+        assert!(result.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_z_and_plain_paths() {
+        let z = parse(LineFormat::Z, b"/a|b|4|1700000000\n/c\n");
+        assert_eq!(entries(&z), vec![(4.0, "/a|b")]);
+        assert_eq!(reasons(&z), vec![(2, "missing rank")]);
+
+        let paths = parse(LineFormat::Paths, b"/srv/my app\n7 /home/u/x\n");
+        assert_eq!(
+            entries(&paths),
+            vec![(0.0, "/srv/my app"), (7.0, "/home/u/x")]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::session::SessionItem;
+use crate::session::{ItemGroup, ItemKey, ListEntry, Selection, SessionItem};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
@@ -13,6 +13,12 @@ pub struct SearchResult {
     pub indices: Vec<usize>,
 }
 
+impl ListEntry for SearchResult {
+    fn session_item(&self) -> &SessionItem {
+        &self.item
+    }
+}
+
 /// Handles fuzzy searching across sessions and directories
 pub struct SearchEngine {
     /// Current search term
@@ -21,10 +27,14 @@ pub struct SearchEngine {
     matcher: SkimMatcherV2,
     /// Current search results
     results: Vec<SearchResult>,
-    /// Selected result index
-    selected_index: Option<usize>,
+    /// Selected result, tracked by identity across searches
+    selection: Selection,
     /// Whether we're currently searching
     is_searching: bool,
+    /// Directories found by the filesystem search, listed after the other results
+    disk_directories: Vec<SessionItem>,
+    /// Some session or directory matches the search term as a whole (not scattered characters)
+    has_good_match: bool,
 }
 
 impl Default for SearchEngine {
@@ -33,15 +43,21 @@ impl Default for SearchEngine {
             search_term: String::new(),
             matcher: SkimMatcherV2::default().use_cache(true),
             results: Vec::new(),
-            selected_index: None,
+            selection: Selection::default(),
             is_searching: false,
+            disk_directories: Vec::new(),
+            has_good_match: false,
         }
     }
 }
 
 impl SearchEngine {
     /// Update search term and perform search
+    /// A new term selects the top result; the same term (list refresh) keeps the selected item
     pub fn update_search(&mut self, term: String, items: &[SessionItem]) {
+        if term != self.search_term {
+            self.selection.clear();
+        }
         self.search_term = term;
         self.is_searching = !self.search_term.is_empty();
 
@@ -49,30 +65,51 @@ impl SearchEngine {
             self.perform_search(items);
         } else {
             self.results.clear();
-            self.selected_index = None;
+            self.selection.clear();
         }
     }
 
-    /// Add character to search term
-    pub fn add_char(&mut self, c: char, items: &[SessionItem]) {
-        self.search_term.push(c);
-        self.update_search(self.search_term.clone(), items);
-    }
-
-    /// Remove last character from search term
-    pub fn backspace(&mut self, items: &[SessionItem]) {
-        self.search_term.pop();
-        self.update_search(self.search_term.clone(), items);
-    }
-
     /// Clear search term
     pub fn clear(&mut self) {
         self.search_term.clear();
         self.results.clear();
-        self.selected_index = None;
+        self.selection.clear();
+        self.disk_directories.clear();
         self.is_searching = false;
     }
 
+    /// Check whether a session or directory matches the search term as a whole
+    pub fn has_good_match(&self) -> bool {
+        self.has_good_match
+    }
+
+    /// Add directories found by the filesystem search for a term
+    /// Ignored if the term is no longer searched; directories already listed are skipped
+    pub fn add_disk_directories(&mut self, term: &str, paths: Vec<String>, items: &[SessionItem]) {
+        if term != self.search_term {
+            return;
+        }
+        for path in paths {
+            if self
+                .disk_directories
+                .iter()
+                .any(|item| item.key() == ItemKey::Directory(path.clone()))
+            {
+                continue;
+            }
+            let session_name = std::path::Path::new(&path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            self.disk_directories.push(SessionItem::Directory {
+                path,
+                session_name,
+                vanished: false,
+            });
+        }
+        self.perform_search(items);
+    }
+
     /// Get current search term
     pub fn search_term(&self) -> &str {
         &self.search_term
@@ -90,44 +127,89 @@ impl SearchEngine {
 
     /// Get selected index
     pub fn selected_index(&self) -> Option<usize> {
-        self.selected_index
+        self.selection.find(&self.results)
+    }
+
+    /// Select the result at an index, clamped to the result list
+    pub fn select_index(&mut self, index: usize) {
+        self.selection.select(&self.results, index);
     }
 
     /// Move selection up
     pub fn move_selection_up(&mut self) {
-        if let Some(selected) = self.selected_index.as_mut() {
-            if *selected == 0 {
-                *selected = self.results.len().saturating_sub(1);
-            } else {
-                *selected = selected.saturating_sub(1);
-            }
-        } else if !self.results.is_empty() {
-            self.selected_index = Some(self.results.len().saturating_sub(1));
-        }
+        self.selection.move_up(&self.results);
     }
 
     /// Move selection down
     pub fn move_selection_down(&mut self) {
-        if let Some(selected) = self.selected_index.as_mut() {
-            if *selected == self.results.len().saturating_sub(1) {
-                *selected = 0;
-            } else {
-                *selected += 1;
-            }
-        } else if !self.results.is_empty() {
-            self.selected_index = Some(0);
-        }
+        self.selection.move_down(&self.results);
     }
 
     /// Get currently selected item
     pub fn selected_item(&self) -> Option<&SessionItem> {
-        self.selected_index
+        self.selected_index()
             .and_then(|i| self.results.get(i))
             .map(|result| &result.item)
     }
 
     /// Perform fuzzy search on items
     fn perform_search(&mut self, items: &[SessionItem]) {
+        let mut matches = self.fuzzy_matches(items);
+
+        // Sort results: sessions first, then by score
+        matches.sort_by(|a, b| {
+            let a_is_session = a.item.is_session() || a.item.is_resurrectable_session();
+            let b_is_session = b.item.is_session() || b.item.is_resurrectable_session();
+
+            match (a_is_session, b_is_session) {
+                (true, false) => std::cmp::Ordering::Less, // a (session) comes first
+                (false, true) => std::cmp::Ordering::Greater, // b (session) comes first
+                _ => b.score.cmp(&a.score),                // Same type, sort by score
+            }
+        });
+        self.has_good_match = matches
+            .iter()
+            .any(|result| result.indices.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+        // Directories found on disk go last, under their own header,
+        // unless they have been listed since (e.g. after a refresh)
+        let unlisted: Vec<SessionItem> = self
+            .disk_directories
+            .iter()
+            .filter(|dir| !items.iter().any(|item| item.has_key(&dir.key())))
+            .cloned()
+            .collect();
+        let mut disk_matches = self.fuzzy_matches(&unlisted);
+        if !disk_matches.is_empty() {
+            disk_matches.sort_by_key(|result| std::cmp::Reverse(result.score));
+            matches.push(SearchResult {
+                item: SessionItem::Header {
+                    group: ItemGroup::FoundOnDisk,
+                    folder: None,
+                    count: disk_matches.len(),
+                    collapsed: false,
+                },
+                score: 0,
+                indices: Vec::new(),
+            });
+            matches.extend(disk_matches);
+        }
+
+        self.results = matches;
+
+        // Keep the selected item selected, or start at the top result (below a header)
+        if self.selection.resolve(&self.results).is_none() {
+            let top = self
+                .results
+                .iter()
+                .position(|result| !matches!(result.item, SessionItem::Header { .. }))
+                .unwrap_or(0);
+            self.selection.select(&self.results, top);
+        }
+    }
+
+    /// Fuzzy match items against the search term, in item order
+    fn fuzzy_matches(&self, items: &[SessionItem]) -> Vec<SearchResult> {
         let mut matches = Vec::new();
 
         for item in items {
@@ -146,34 +228,7 @@ impl SearchEngine {
             }
         }
 
-        // Sort results: sessions first, then by score
-        matches.sort_by(|a, b| {
-            let a_is_session = a.item.is_session() || a.item.is_resurrectable_session();
-            let b_is_session = b.item.is_session() || b.item.is_resurrectable_session();
-
-            match (a_is_session, b_is_session) {
-                (true, false) => std::cmp::Ordering::Less, // a (session) comes first
-                (false, true) => std::cmp::Ordering::Greater, // b (session) comes first
-                _ => b.score.cmp(&a.score),                // Same type, sort by score
-            }
-        });
-
-        self.results = matches;
-
-        // Update selected index
-        if self.results.is_empty() {
-            self.selected_index = None;
-        } else {
-            match self.selected_index {
-                Some(idx) if idx >= self.results.len() => {
-                    self.selected_index = Some(self.results.len().saturating_sub(1));
-                }
-                None => {
-                    self.selected_index = Some(0);
-                }
-                _ => {} // Keep current selection if valid
-            }
-        }
+        matches
     }
 
     /// Get the display text used for searching (matches what's rendered)
@@ -199,6 +254,56 @@ impl SearchEngine {
                 // For directories, we search the full path as displayed
                 path.clone()
             }
+            SessionItem::Header { .. } => item.name().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directory(path: &str) -> SessionItem {
+        SessionItem::Directory {
+            path: path.to_string(),
+            session_name: String::new(),
+            vanished: false,
         }
     }
+
+    #[test]
+    fn test_new_term_selects_top_result() {
+        let items = vec![directory("/home/u/web"), directory("/home/u/wiki")];
+        let mut engine = SearchEngine::default();
+        engine.update_search("w".to_string(), &items);
+        engine.select_index(1);
+        let selected = engine.selected_item().map(SessionItem::key);
+
+        // Refreshing the same term keeps the selected item
+        engine.update_search("w".to_string(), &items);
+        assert_eq!(engine.selected_item().map(SessionItem::key), selected);
+
+        // Still listed, but no longer the top result
+        engine.update_search("u/w".to_string(), &items);
+        assert_eq!(engine.results().len(), 2);
+        assert_eq!(engine.selected_index(), Some(0));
+        assert_ne!(engine.selected_item().map(SessionItem::key), selected);
+    }
+
+    #[test]
+    fn test_disk_directories_follow_other_results() {
+        let items = vec![directory("/home/u/web"), directory("/home/u/wiki")];
+        let mut engine = SearchEngine::default();
+        engine.update_search("wbapp".to_string(), &items);
+        assert!(!engine.has_good_match());
+
+        let found = vec!["/home/u/web".to_string(), "/srv/webapp".to_string()];
+        engine.add_disk_directories("stale", found.clone(), &items);
+        assert_eq!(engine.results().len(), 0);
+
+        engine.add_disk_directories("wbapp", found, &items);
+        let names: Vec<&str> = engine.results().iter().map(|r| r.item.name()).collect();
+        assert_eq!(names, vec!["Found on disk", "webapp"]);
+        assert_eq!(engine.selected_index(), Some(1));
+    }
 }
@@ -0,0 +1,168 @@
+use super::parser::{self, LineFormat, ParseResult, SkippedLine};
+use super::{ZoxideCommand, ZoxideDirectory};
+use std::collections::{BTreeMap, HashSet};
+
+/// A place directories are read from, configured with `directory_sources`
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectorySource {
+    /// `zoxide query -l -s` ("score path" lines)
+    Zoxide,
+    /// `fasd -d` ("score path" lines)
+    Fasd,
+    /// `autojump -s` ("score:<TAB>path" lines followed by totals)
+    Autojump,
+    /// The z database ("path|rank|time" lines)
+    Z,
+    /// A text file with one path (or "score path") per line
+    File(String),
+    /// A shell command printing one path (or "score path") per line
+    Command(String),
+}
+
+impl DirectorySource {
+    /// Parse a `directory_sources` entry: `zoxide`, `fasd`, `autojump`, `z`,
+    /// `file:<path>` or `command:<shell command>`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if let Some(path) = spec.strip_prefix("file:") {
+            return Ok(DirectorySource::File(path.trim().to_string()));
+        }
+        if let Some(command) = spec.strip_prefix("command:") {
+            return Ok(DirectorySource::Command(command.trim().to_string()));
+        }
+        match spec.to_lowercase().as_str() {
+            "zoxide" => Ok(DirectorySource::Zoxide),
+            "fasd" => Ok(DirectorySource::Fasd),
+            "autojump" => Ok(DirectorySource::Autojump),
+            "z" => Ok(DirectorySource::Z),
+            _ => Err(format!("Unknown directory source '{}'", spec)),
+        }
+    }
+
+    /// Name shown in error messages
+    pub fn name(&self) -> String {
+        match self {
+            DirectorySource::Zoxide => "zoxide".to_string(),
+            DirectorySource::Fasd => "fasd".to_string(),
+            DirectorySource::Autojump => "autojump".to_string(),
+            DirectorySource::Z => "z".to_string(),
+            DirectorySource::File(path) => format!("file {}", path),
+            DirectorySource::Command(command) => format!("command '{}'", command),
+        }
+    }
+
+    /// Command printing the directories of this source
+    pub fn command(&self, zoxide: &ZoxideCommand) -> Vec<String> {
+        let args: &[&str] = match self {
+            DirectorySource::Zoxide => return zoxide.query(),
+            DirectorySource::Fasd => &["fasd", "-d"],
+            DirectorySource::Autojump => &["autojump", "-s"],
+            DirectorySource::Z => &["sh", "-c", "cat \"${_Z_DATA:-$HOME/.z}\""],
+            DirectorySource::File(path) => {
+                // Expand a leading "~/" like a shell would for an unquoted path
+                return vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "p=$1; case $p in \"~/\"*) p=\"$HOME/${p#\"~/\"}\";; esac; cat -- \"$p\""
+                        .to_string(),
+                    "sh".to_string(),
+                    path.clone(),
+                ];
+            }
+            DirectorySource::Command(command) => {
+                return vec!["sh".to_string(), "-c".to_string(), command.clone()]
+            }
+        };
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Environment variables for the command of this source
+    pub fn env(&self, zoxide: &ZoxideCommand) -> BTreeMap<String, String> {
+        match self {
+            DirectorySource::Zoxide => zoxide.env.clone(),
+            _ => BTreeMap::new(),
+        }
+    }
+
+    /// Line format of the command output
+    pub fn format(&self) -> LineFormat {
+        match self {
+            DirectorySource::Zoxide | DirectorySource::Fasd => LineFormat::Scored,
+            DirectorySource::Autojump => LineFormat::Autojump,
+            DirectorySource::Z => LineFormat::Z,
+            DirectorySource::File(_) | DirectorySource::Command(_) => LineFormat::Paths,
+        }
+    }
+
+    /// Parse the command output into directories, in output order, without session names
+    pub fn parse_output(&self, output: &[u8]) -> ParseResult {
+        parser::parse(self.format(), output)
+    }
+}
+
+/// What the last read of a directory source produced (for the diagnostics screen)
+#[derive(Debug, Clone, Default)]
+pub struct SourceDiagnostics {
+    /// Source name
+    pub name: String,
+    /// Command line that was run
+    pub command: String,
+    /// Number of directories read
+    pub directories: usize,
+    /// Output lines that could not be parsed
+    pub skipped: Vec<SkippedLine>,
+    /// Why the last read failed, if it did (directories and skipped lines are from the read before)
+    pub error: Option<String>,
+}
+
+/// Merge the directories of several sources, in source order
+/// A directory listed by more than one source keeps the entry of the first
+pub fn merge<'a>(results: impl IntoIterator<Item = &'a [ZoxideDirectory]>) -> Vec<ZoxideDirectory> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .flatten()
+        .filter(|dir| seen.insert(dir.directory.trim_end_matches('/').to_string()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(directories: &[ZoxideDirectory]) -> Vec<(f64, &str)> {
+        directories
+            .iter()
+            .map(|dir| (dir.ranking, dir.directory.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_source_specs() {
+        assert_eq!(
+            DirectorySource::parse("zoxide"),
+            Ok(DirectorySource::Zoxide)
+        );
+        assert_eq!(
+            DirectorySource::parse("file:~/projects.txt"),
+            Ok(DirectorySource::File("~/projects.txt".to_string()))
+        );
+        assert_eq!(
+            DirectorySource::parse("command:fd -t d . ~/src"),
+            Ok(DirectorySource::Command("fd -t d . ~/src".to_string()))
+        );
+        assert!(DirectorySource::parse("mlocate").is_err());
+    }
+
+    #[test]
+    fn test_merge_keeps_first_source() {
+        let first = DirectorySource::Zoxide
+            .parse_output(b"9 /a\n5 /b\n")
+            .directories;
+        let second = DirectorySource::File(String::new())
+            .parse_output(b"/b/\n/c\n")
+            .directories;
+        let merged = merge([first.as_slice(), second.as_slice()]);
+        assert_eq!(paths(&merged), vec![(9.0, "/a"), (5.0, "/b"), (0.0, "/c")]);
+    }
+}